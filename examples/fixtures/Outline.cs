@@ -0,0 +1,43 @@
+// 大纲测试用例，覆盖 C# 的各种声明
+using System;
+
+namespace Fixtures
+{
+    public delegate void Handler(string message);
+
+    public interface IShape
+    {
+        double Area();
+    }
+
+    public enum Color
+    {
+        Red,
+        Green
+    }
+
+    public record Point(int X, int Y);
+
+    public class Shape : IShape
+    {
+        public const int Limit = 10;
+        private string name;
+
+        public string Name { get; set; }
+
+        public Shape(string name)
+        {
+            this.name = name;
+        }
+
+        public double Area()
+        {
+            return 0;
+        }
+    }
+
+    public struct Size
+    {
+        public int Width;
+    }
+}
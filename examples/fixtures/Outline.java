@@ -0,0 +1,49 @@
+package com.example.fixtures;
+
+import java.util.List;
+
+@interface Audit {
+    String value();
+}
+
+public class Outline {
+    public static final int LIMIT = 10;
+    private String name;
+
+    public Outline(String name) {
+        this.name = name;
+    }
+
+    public String getName() {
+        return name;
+    }
+
+    static class Inner {
+        void run() {}
+    }
+}
+
+interface Shape {
+    double PI = 3.14;
+
+    double area();
+}
+
+enum Color {
+    RED,
+    GREEN;
+
+    Color next() {
+        return GREEN;
+    }
+}
+
+record Point(int x, int y) {
+    Point {
+        assert x >= 0;
+    }
+
+    int sum() {
+        return x + y;
+    }
+}
@@ -0,0 +1,39 @@
+/* 大纲测试用例，覆盖 C 的各种声明 */
+#include <stdio.h>
+
+#define LIMIT 10
+#define SQUARE(x) ((x) * (x))
+
+int counter = 0;
+
+struct Point {
+    int x;
+    int y;
+};
+
+union Value {
+    int i;
+    float f;
+};
+
+enum Color {
+    RED,
+    GREEN
+};
+
+typedef struct {
+    char *name;
+} Person;
+
+typedef unsigned long size;
+
+int add(int a, int b);
+
+static int add(int a, int b) {
+    return a + b;
+}
+
+int main(void) {
+    struct Point p = {1, 2};
+    return add(p.x, p.y);
+}
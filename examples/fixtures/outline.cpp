@@ -0,0 +1,42 @@
+// 大纲测试用例，覆盖 C++ 的各种声明
+#include <string>
+
+#define LIMIT 10
+
+namespace geometry {
+
+enum class Color { Red, Green };
+
+struct Point {
+    int x;
+    int y;
+};
+
+class Shape {
+public:
+    Shape(std::string name) : name(name) {}
+    virtual double area() const = 0;
+    std::string getName() const { return name; }
+
+private:
+    std::string name;
+};
+
+using Points = std::vector<Point>;
+
+typedef double real;
+
+template <typename T>
+T max(T a, T b) {
+    return a > b ? a : b;
+}
+
+}  // namespace geometry
+
+double geometry::Shape::area() const {
+    return 0;
+}
+
+int main() {
+    return 0;
+}
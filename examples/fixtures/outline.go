@@ -0,0 +1,31 @@
+// 大纲测试用例，覆盖 Go 的各种声明
+package fixtures
+
+import "fmt"
+
+const Limit = 10
+
+const (
+	Red = iota
+	Green
+)
+
+var counter int
+
+type Celsius float64
+
+type Shape interface {
+	Area() float64
+}
+
+type Rectangle struct {
+	Width, Height float64
+}
+
+func (r *Rectangle) Area() float64 {
+	return r.Width * r.Height
+}
+
+func Describe(s Shape) {
+	fmt.Println(s.Area())
+}
@@ -0,0 +1,51 @@
+// 大纲测试用例，覆盖 JavaScript 的各种声明
+import { readFile } from "fs";
+
+export const LIMIT = 10;
+
+const multiply = (a, b) => a * b;
+
+const square = function (x) {
+  return x * x;
+};
+
+let counter = 0;
+
+function plain(a) {
+  const local = a + 1;
+  return local;
+}
+
+function* ids() {
+  yield 1;
+}
+
+class Shape {
+  static count = 0;
+  name = "shape";
+
+  constructor(name) {
+    this.name = name;
+  }
+
+  get label() {
+    return this.name;
+  }
+
+  area() {
+    return 0;
+  }
+}
+
+export default class Circle extends Shape {
+  area() {
+    return 3.14;
+  }
+}
+
+const car = {
+  brand: "Toyota",
+  start() {
+    return true;
+  },
+};
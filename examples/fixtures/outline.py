@@ -0,0 +1,37 @@
+"""大纲测试用例，覆盖 Python 的各种声明"""
+import functools
+
+LIMIT = 10
+
+
+def plain(a, b=1):
+    return a + b
+
+
+@functools.lru_cache(maxsize=None)
+def cached(n):
+    return n
+
+
+class Shape:
+    """A shape."""
+
+    sides = 0
+
+    def __init__(self, name):
+        self.name = name
+
+    @property
+    def label(self):
+        return self.name
+
+    @staticmethod
+    def create():
+        return Shape("shape")
+
+    class Meta:
+        ordering = ["name"]
+
+
+async def fetch(url):
+    return url
@@ -0,0 +1,55 @@
+//! 大纲测试用例，覆盖 Rust 的各种声明
+use std::fmt;
+
+pub mod shapes {
+    pub const MAX_SIDES: usize = 8;
+    pub static ORIGIN: (f64, f64) = (0.0, 0.0);
+
+    /// 形状
+    pub trait Shape {
+        const SIDES: usize;
+        type Unit;
+        fn area(&self) -> f64;
+        fn name(&self) -> String {
+            String::from("shape")
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct Rectangle {
+        pub width: f64,
+        pub height: f64,
+    }
+
+    pub enum Color {
+        Red,
+        Rgb(u8, u8, u8),
+    }
+
+    pub union Bits {
+        int: u32,
+        float: f32,
+    }
+
+    impl Rectangle {
+        pub const UNIT: f64 = 1.0;
+
+        pub fn new(width: f64, height: f64) -> Self {
+            Rectangle { width, height }
+        }
+    }
+}
+
+pub type Pair = (i32, i32);
+
+macro_rules! square {
+    ($x:expr) => {
+        $x * $x
+    };
+}
+
+impl fmt::Display for shapes::Rectangle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
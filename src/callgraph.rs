@@ -0,0 +1,322 @@
+use colored::Colorize;
+use serde_json::json;
+use std::{collections::HashSet, path::PathBuf};
+
+use crate::{
+    get_language, get_symbol_query, lang::SymbolQuery, parse_code, read_source,
+    refs::get_enclosing_symbol, SourceEncoding,
+};
+
+/// 不在任何函数内的调用，如脚本语言的顶层代码
+const TOP_LEVEL: &str = "<top level>";
+
+/**
+* 调用关系：caller 在 path:line 处调用了 callee
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallEdge {
+    /// 调用方的限定名称
+    pub caller: String,
+    /// 被调用函数的名称
+    pub callee: String,
+    pub path: String,
+    pub line: usize,
+}
+
+/**
+* 调用图的遍历方向
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallDirection {
+    Callers,
+    Callees,
+}
+
+/**
+* 获取源码中的所有函数调用
+*/
+pub fn find_calls(code: &str, path: &str, symbol_query: &dyn SymbolQuery) -> Vec<CallEdge> {
+    let Some(tree) = parse_code(code, symbol_query) else {
+        return vec![];
+    };
+    let call_kinds = symbol_query.get_call_kinds();
+    let mut calls = vec![];
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if call_kinds.contains(&node.kind()) {
+            if let Some(callee) = symbol_query.get_callee(&node) {
+                calls.push(CallEdge {
+                    caller: get_enclosing_symbol(code, &node, symbol_query)
+                        .unwrap_or(TOP_LEVEL.to_string()),
+                    callee: code[callee.byte_range()].to_string(),
+                    path: path.to_string(),
+                    line: node.start_position().row + 1,
+                });
+            }
+        }
+        let children: Vec<_> = node.children(&mut node.walk()).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    calls
+}
+
+/**
+* 名称是否指向该限定名称，如 `hint` 与 `CodeHinter::hint`
+*/
+fn name_matches(qualified_name: &str, name: &str) -> bool {
+    qualified_name == name
+        || qualified_name.ends_with(&format!(".{name}"))
+        || qualified_name.ends_with(&format!("::{name}"))
+}
+
+/**
+* 取限定名称的最后一段
+*/
+fn short_name(name: &str) -> &str {
+    name.rsplit(['.', ':']).next().unwrap_or(name)
+}
+
+/**
+* 近似的调用图，被调用函数按语言和名称匹配
+*/
+pub struct CallGraph {
+    pub edges: Vec<CallEdge>,
+}
+
+impl CallGraph {
+    /**
+     * 从文件构建调用图，只保留调用同一语言中已定义函数（defined，语言和名称）的边
+     */
+    pub fn build(
+        pathes: &[PathBuf],
+        defined: &HashSet<(&str, &str)>,
+        encoding: SourceEncoding,
+    ) -> CallGraph {
+        let mut edges = vec![];
+        for path in pathes {
+            let Some(path_extension) = path.extension().and_then(|ext| ext.to_str()) else {
+                continue;
+            };
+            if !crate::support_symbol(path_extension) {
+                continue;
+            }
+            let code = read_source(path, encoding).unwrap_or("".to_string());
+            let symbol_query = get_symbol_query(path_extension);
+            edges.extend(
+                find_calls(&code, path.to_str().unwrap(), symbol_query.as_ref())
+                    .into_iter()
+                    .filter(|edge| {
+                        defined.contains(&(get_language(&edge.path), edge.callee.as_str()))
+                    }),
+            );
+        }
+        CallGraph { edges }
+    }
+
+    /**
+     * 从 name 出发遍历调用图，返回 (深度, 边)，深度从 0 开始
+     */
+    pub fn walk(
+        &self,
+        name: &str,
+        direction: CallDirection,
+        depth: usize,
+    ) -> Vec<(usize, &CallEdge)> {
+        let mut result = vec![];
+        let mut visited = HashSet::new();
+        self.walk_inner((name, None), direction, 0, depth, &mut visited, &mut result);
+        result
+    }
+
+    /**
+     * 遍历 (名称, 语言) 的调用关系，第一层之后只沿着与上一条边相同语言的边继续遍历
+     */
+    fn walk_inner<'a>(
+        &'a self,
+        (name, language): (&str, Option<&'a str>),
+        direction: CallDirection,
+        level: usize,
+        depth: usize,
+        visited: &mut HashSet<(String, Option<&'a str>)>,
+        result: &mut Vec<(usize, &'a CallEdge)>,
+    ) {
+        if level >= depth || !visited.insert((name.to_string(), language)) {
+            return;
+        }
+        for edge in &self.edges {
+            let edge_language = get_language(&edge.path);
+            if language.is_some_and(|language| language != edge_language) {
+                continue;
+            }
+            let (matched, next) = match direction {
+                CallDirection::Callers => (edge.callee == short_name(name), &edge.caller),
+                CallDirection::Callees => (name_matches(&edge.caller, name), &edge.callee),
+            };
+            if matched {
+                result.push((level, edge));
+                self.walk_inner(
+                    (next, Some(edge_language)),
+                    direction,
+                    level + 1,
+                    depth,
+                    visited,
+                    result,
+                );
+            }
+        }
+    }
+
+    /**
+     * 以树的形式打印调用关系
+     */
+    pub fn print_tree(&self, name: &str, direction: CallDirection, depth: usize) {
+        println!("{}", name.purple());
+        for (level, edge) in self.walk(name, direction, depth) {
+            let target = match direction {
+                CallDirection::Callers => &edge.caller,
+                CallDirection::Callees => &edge.callee,
+            };
+            println!(
+                "{}{} {}",
+                "  ".repeat(level + 1),
+                target,
+                format!("({}:{})", edge.path, edge.line).bright_black()
+            );
+        }
+    }
+
+    /**
+     * 导出为 Graphviz DOT 格式
+     */
+    pub fn to_dot(&self, name: &str, direction: CallDirection, depth: usize) -> String {
+        let mut dot = String::from("digraph calls {\n");
+        let mut written = HashSet::new();
+        for (_, edge) in self.walk(name, direction, depth) {
+            let line = format!("  {:?} -> {:?};\n", edge.caller, edge.callee);
+            if written.insert(line.clone()) {
+                dot.push_str(&line);
+            }
+        }
+        dot.push('}');
+        dot
+    }
+
+    /**
+     * 导出为 JSON 数组
+     */
+    pub fn to_json(&self, name: &str, direction: CallDirection, depth: usize) -> String {
+        let edges: Vec<_> = self
+            .walk(name, direction, depth)
+            .into_iter()
+            .map(|(level, edge)| {
+                json!({
+                    "caller": edge.caller,
+                    "callee": edge.callee,
+                    "path": edge.path,
+                    "line": edge.line,
+                    "depth": level,
+                })
+            })
+            .collect();
+        serde_json::to_string_pretty(&edges).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lang::{CSharpQuery, JavascriptQuery, PythonQuery, RustQuery},
+        tests::TempFile,
+    };
+
+    #[test]
+    fn test_call_graph() {
+        let code = "fn a() { b(); c::d(); }\nfn b() { x.c(); }\n";
+        let calls: Vec<(String, String)> = find_calls(code, "main.rs", &RustQuery)
+            .into_iter()
+            .map(|edge| (edge.caller, edge.callee))
+            .collect();
+        let expected = [("a", "b"), ("a", "d"), ("b", "c")];
+        assert_eq!(
+            calls,
+            expected.map(|(caller, callee)| (caller.to_string(), callee.to_string()))
+        );
+
+        let call_graph = CallGraph {
+            edges: find_calls(code, "main.rs", &RustQuery),
+        };
+        let callers: Vec<&str> = call_graph
+            .walk("c", CallDirection::Callers, 3)
+            .iter()
+            .map(|(_, edge)| edge.caller.as_str())
+            .collect();
+        assert_eq!(callers, vec!["b", "a"]);
+    }
+
+    #[test]
+    fn test_call_graph_languages() {
+        let rust = TempFile::new("calls.rs", "fn a() { b(); }\nfn b() { c(); }\n");
+        let go = TempFile::new("calls.go", "package p\n\nfunc b() { c() }\nfunc c() {}\n");
+        let pathes = [PathBuf::from(rust.path()), PathBuf::from(go.path())];
+        // Go 中定义的 c 不能作为 Rust 代码中调用的 c
+        let defined = HashSet::from([("rs", "b"), ("go", "c")]);
+        let call_graph = CallGraph::build(&pathes, &defined, SourceEncoding::default());
+        let edges: Vec<(&str, &str, &str)> = call_graph
+            .edges
+            .iter()
+            .map(|edge| {
+                let language = get_language(&edge.path);
+                (language, edge.caller.as_str(), edge.callee.as_str())
+            })
+            .collect();
+        assert_eq!(edges, vec![("rs", "a", "b"), ("go", "b", "c")]);
+
+        // 从 Rust 的 a 出发不会经过 Go 的 b 走到 c
+        let callees: Vec<&str> = call_graph
+            .walk("a", CallDirection::Callees, 3)
+            .iter()
+            .map(|(_, edge)| edge.callee.as_str())
+            .collect();
+        assert_eq!(callees, vec!["b"]);
+    }
+
+    #[test]
+    fn test_callee_names() {
+        let callees = |code: &str, symbol_query: &dyn SymbolQuery| -> Vec<String> {
+            find_calls(code, "", symbol_query)
+                .into_iter()
+                .map(|edge| edge.callee)
+                .collect()
+        };
+        // `make(x)()` 的外层和内层调用都指向 make，成员调用取成员名，下标调用没有名称
+        assert_eq!(
+            callees(
+                "fn a() { make(x)(); handlers[0](); self.shape.area(); }",
+                &RustQuery
+            ),
+            vec!["make", "make", "area"]
+        );
+        assert_eq!(
+            callees(
+                "function a() { make(x)(); obj[key](); obj.run(); new ui.Button(); }",
+                &JavascriptQuery
+            ),
+            vec!["make", "make", "run", "Button"]
+        );
+        assert_eq!(
+            callees(
+                "def a():\n    make(x)()\n    handlers[key]()\n    obj.run()\n",
+                &PythonQuery
+            ),
+            vec!["make", "make", "run"]
+        );
+        assert_eq!(
+            callees(
+                "class A { void M() { Parse<int>(); items[0](); shape.Area(); } }",
+                &CSharpQuery
+            ),
+            vec!["Parse", "Area"]
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use std::path::Path;
+use tree_sitter::Point;
+
+use crate::{get_language, lang::SymbolQuery, parse_code, CodeIndex};
+
+/**
+* 源码中的位置，行和列从 1 开始，列按字符计算
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/**
+* 解析 `file:line:col` 格式的位置，列可以省略
+*/
+pub fn parse_location(location: &str) -> Option<Location> {
+    let (rest, last) = location.rsplit_once(':')?;
+    let last = last.parse::<usize>().ok()?;
+    if let Some((path, line)) = rest.rsplit_once(':') {
+        if let Ok(line) = line.parse::<usize>() {
+            return Some(Location {
+                path: path.to_string(),
+                line,
+                column: last,
+            });
+        }
+    }
+    Some(Location {
+        path: rest.to_string(),
+        line: last,
+        column: 1,
+    })
+}
+
+/**
+* 获取指定位置的标识符
+*/
+pub fn get_identifier_at(
+    code: &str,
+    line: usize,
+    column: usize,
+    symbol_query: &dyn SymbolQuery,
+) -> Option<String> {
+    let line_text = code.lines().nth(line.checked_sub(1)?)?;
+    // 列按字符计算，tree-sitter 使用字节偏移
+    let byte_column = line_text
+        .char_indices()
+        .nth(column.saturating_sub(1))
+        .map(|(offset, _)| offset)
+        .unwrap_or(line_text.len());
+    let point = Point::new(line - 1, byte_column);
+
+    let tree = parse_code(code, symbol_query)?;
+    let node = tree
+        .root_node()
+        .named_descendant_for_point_range(point, point)?;
+    if symbol_query.get_identifier_kinds().contains(&node.kind()) {
+        Some(code[node.byte_range()].to_string())
+    } else {
+        None
+    }
+}
+
+/**
+* 获取源码中所有导入语句的文本
+*/
+pub fn get_imports(code: &str, symbol_query: &dyn SymbolQuery) -> Vec<String> {
+    let Some(tree) = parse_code(code, symbol_query) else {
+        return vec![];
+    };
+    let import_kinds = symbol_query.get_import_kinds();
+    let mut imports = vec![];
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if import_kinds.contains(&node.kind()) {
+            imports.push(code[node.byte_range()].to_string());
+        } else {
+            stack.extend(node.children(&mut node.walk()));
+        }
+    }
+    imports
+}
+
+/**
+* 候选定义所在文件的模块名，`mod.rs`、`index.js`、`__init__.py` 使用目录名
+*/
+fn get_module_name(path: &Path) -> Option<&str> {
+    let stem = path.file_stem()?.to_str()?;
+    if matches!(stem, "mod" | "index" | "__init__") {
+        path.parent()?.file_name()?.to_str()
+    } else {
+        Some(stem)
+    }
+}
+
+/**
+* 从索引中查找名称的定义，只保留与所在位置相同语言的定义，按同文件、同包（目录）、已导入、其他的顺序排列
+*/
+pub fn find_definitions<'a>(
+    index: &'a [CodeIndex],
+    name: &str,
+    location: &Location,
+    imports: &[String],
+) -> Vec<&'a CodeIndex> {
+    let source_path = Path::new(&location.path);
+    let rank = |item: &CodeIndex| {
+        let path = Path::new(&item.path);
+        if path == source_path {
+            0
+        } else if path.parent() == source_path.parent() {
+            1
+        } else if get_module_name(path)
+            .is_some_and(|module| imports.iter().any(|import| import.contains(module)))
+        {
+            2
+        } else {
+            3
+        }
+    };
+    let language = get_language(&location.path);
+    let mut definitions: Vec<&CodeIndex> = index
+        .iter()
+        .filter(|item| item.line_code == name && get_language(&item.path) == language)
+        .collect();
+    definitions.sort_by_key(|item| (rank(item), item.line.abs_diff(location.line)));
+    definitions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lang::RustQuery, tests::code_index, SymbolKind};
+
+    #[test]
+    fn test_identifier_at_location() {
+        let location = parse_location("src/main.rs:3:10").unwrap();
+        assert_eq!(
+            (location.path.as_str(), location.line, location.column),
+            ("src/main.rs", 3, 10)
+        );
+        let location = parse_location("src/main.rs:3").unwrap();
+        assert_eq!(
+            (location.path.as_str(), location.line, location.column),
+            ("src/main.rs", 3, 1)
+        );
+        assert!(parse_location("src/main.rs").is_none());
+
+        let code = "fn area() {}\nfn main() {\n    let a = area();\n}\n";
+        assert_eq!(
+            get_identifier_at(code, 3, 14, &RustQuery),
+            Some("area".to_string())
+        );
+        assert_eq!(get_identifier_at(code, 3, 1, &RustQuery), None);
+    }
+
+    #[test]
+    fn test_find_definitions() {
+        let index = [
+            code_index("/p/src/shapes.rs", "shapes::Rectangle", SymbolKind::Struct),
+            code_index("/p/go/shapes.go", "Rectangle", SymbolKind::Struct),
+            code_index("/p/go/main.go", "Rectangle", SymbolKind::Function),
+            code_index("/p/c/shape.h", "Rectangle", SymbolKind::Struct),
+        ];
+        let paths = |path: &str, imports: &[String]| -> Vec<&str> {
+            let location = Location {
+                path: path.to_string(),
+                line: 1,
+                column: 1,
+            };
+            find_definitions(&index, "Rectangle", &location, imports)
+                .iter()
+                .map(|item| item.path.as_str())
+                .collect()
+        };
+        // 同名的 Rust 结构体不能作为 Go 代码的定义
+        assert_eq!(
+            paths("/p/go/main.go", &[]),
+            vec!["/p/go/main.go", "/p/go/shapes.go"]
+        );
+        assert_eq!(
+            paths("/p/main.rs", &["use shapes::Rectangle;".to_string()]),
+            vec!["/p/src/shapes.rs"]
+        );
+        assert_eq!(paths("/p/c/main.cpp", &[]), vec!["/p/c/shape.h"]);
+    }
+}
@@ -0,0 +1,85 @@
+use colored::Colorize;
+use tree_sitter::Node;
+
+use crate::{
+    lang::{get_value_preview, SymbolQuery},
+    parse_code,
+};
+
+/**
+* tree-sitter 在语法错误处生成的节点
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    /// 从 1 开始，按字符计算，与 `def` 的 `file:line:col` 一致
+    pub column: usize,
+    /// 为 true 时是解析器补全的缺失节点（MISSING），否则是无法识别的代码（ERROR）
+    pub missing: bool,
+    /// ERROR 节点为出错的代码片段，MISSING 节点为缺失的节点类型，如 `;`
+    pub text: String,
+}
+
+impl ParseError {
+    pub fn label(&self) -> &'static str {
+        if self.missing {
+            "MISSING"
+        } else {
+            "ERROR"
+        }
+    }
+}
+
+/**
+* 查找源码中的语法错误，ERROR 节点内部不再展开
+*/
+pub fn find_parse_errors(code: &str, symbol_query: &dyn SymbolQuery) -> Vec<ParseError> {
+    let Some(tree) = parse_code(code, symbol_query) else {
+        return vec![];
+    };
+    let mut errors = vec![];
+    if !tree.root_node().has_error() {
+        return errors;
+    }
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if node.is_error() || node.is_missing() {
+            let position = node.start_position();
+            // tree-sitter 的列是字节偏移，换算为字符数
+            let line_start = node.start_byte() - position.column;
+            errors.push(ParseError {
+                line: position.row + 1,
+                column: code[line_start..node.start_byte()].chars().count() + 1,
+                missing: node.is_missing(),
+                text: if node.is_missing() {
+                    node.kind().to_string()
+                } else {
+                    get_value_preview(code, &node)
+                },
+            });
+            continue;
+        }
+        if node.has_error() {
+            // 逆序入栈，保证按源码顺序输出
+            let children: Vec<Node> = node.children(&mut node.walk()).collect();
+            stack.extend(children.into_iter().rev());
+        }
+    }
+    errors
+}
+
+/**
+* 输出一个文件中的语法错误
+*/
+pub fn print_parse_errors(path: &str, errors: &[ParseError]) {
+    println!("{}", path.green());
+    for error in errors {
+        println!(
+            "  {}:{} {} {}",
+            error.line,
+            error.column,
+            error.label().red(),
+            error.text
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    env, io,
+    path::Path,
+    process::{Command, ExitStatus},
+};
+
+/**
+* 获取用户的编辑器，依次读取 $VISUAL、$EDITOR，都未设置时使用 vi
+*/
+pub fn get_editor() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/**
+* 按编辑器跳转到指定行的参数格式生成命令行，编辑器可以带参数，如 `code --wait`
+* 无法识别的编辑器只打开文件
+*/
+pub fn get_editor_args(editor: &str, path: &str, line: usize) -> Vec<String> {
+    let mut args: Vec<String> = editor.split_whitespace().map(str::to_string).collect();
+    let program = args
+        .first()
+        .and_then(|program| Path::new(program).file_stem())
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    match program.as_str() {
+        "vi" | "vim" | "nvim" | "gvim" | "mvim" | "view" | "nano" | "pico" | "emacs"
+        | "emacsclient" | "kak" | "gedit" | "mg" | "joe" | "ne" => {
+            args.push(format!("+{line}"));
+            args.push(path.to_string());
+        }
+        "code" | "code-insiders" | "codium" | "vscodium" | "cursor" | "windsurf" => {
+            args.push("--goto".to_string());
+            args.push(format!("{path}:{line}"));
+        }
+        "subl" | "sublime_text" | "zed" | "hx" | "helix" | "micro" | "atom" => {
+            args.push(format!("{path}:{line}"));
+        }
+        "idea" | "pycharm" | "clion" | "goland" | "webstorm" | "rustrover" | "phpstorm" => {
+            args.push("--line".to_string());
+            args.push(line.to_string());
+            args.push(path.to_string());
+        }
+        _ => args.push(path.to_string()),
+    }
+    args
+}
+
+/**
+* 使用用户的编辑器打开文件并跳转到指定行，等待编辑器退出
+*/
+pub fn open_in_editor(path: &str, line: usize) -> io::Result<ExitStatus> {
+    let editor = get_editor();
+    let args = get_editor_args(&editor, path, line);
+    Command::new(&args[0])
+        .args(&args[1..])
+        .status()
+        .map_err(|err| io::Error::new(err.kind(), format!("无法启动编辑器 {}: {err}", args[0])))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editor_args() {
+        assert_eq!(
+            get_editor_args("nvim", "src/lib.rs", 12),
+            vec!["nvim", "+12", "src/lib.rs"]
+        );
+        assert_eq!(
+            get_editor_args("emacsclient -nw", "a.py", 3),
+            vec!["emacsclient", "-nw", "+3", "a.py"]
+        );
+        assert_eq!(
+            get_editor_args("/usr/local/bin/code --wait", "a.go", 7),
+            vec!["/usr/local/bin/code", "--wait", "--goto", "a.go:7"]
+        );
+        assert_eq!(get_editor_args("hx", "a.rs", 1), vec!["hx", "a.rs:1"]);
+        assert_eq!(
+            get_editor_args("idea", "A.java", 5),
+            vec!["idea", "--line", "5", "A.java"]
+        );
+        assert_eq!(get_editor_args("ed", "a.c", 9), vec!["ed", "a.c"]);
+    }
+}
@@ -0,0 +1,141 @@
+use encoding_rs::Encoding;
+use std::{borrow::Cow, fs, path::Path, str::FromStr};
+
+/**
+* `--encoding` 指定的源码编码，默认（`auto`）只识别 BOM，没有 BOM 时按 UTF-8 读取
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SourceEncoding(Option<&'static Encoding>);
+
+impl FromStr for SourceEncoding {
+    type Err = String;
+
+    /**
+     * 支持 gbk、shift_jis、utf-16le 等 WHATWG 编码名称，`auto` 表示只识别 BOM
+     */
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "auto" | "" => Ok(SourceEncoding(None)),
+            label => Encoding::for_label(label.as_bytes())
+                .map(|encoding| SourceEncoding(Some(encoding)))
+                .ok_or(format!("未知的编码: {label}")),
+        }
+    }
+}
+
+/**
+* 内容是否需要转码：带 BOM 或指定了编码
+*/
+pub(crate) fn needs_transcoding(bytes: &[u8], encoding: SourceEncoding) -> bool {
+    Encoding::for_bom(bytes).is_some() || encoding.0.is_some()
+}
+
+/**
+* 解码源码，BOM 优先于 `--encoding`，都没有时按 UTF-8 解码
+*
+* 转码只改变字节表示，不增删换行，行号与原文件一致；无法解码的字节以替换字符代替
+*/
+pub fn decode_source(bytes: &[u8], encoding: SourceEncoding) -> Cow<'_, str> {
+    match encoding.0 {
+        // decode 会识别并去掉 BOM
+        Some(encoding) => encoding.decode(bytes).0,
+        None => match Encoding::for_bom(bytes) {
+            Some((encoding, _)) => encoding.decode(bytes).0,
+            None => String::from_utf8_lossy(bytes),
+        },
+    }
+}
+
+/**
+* 读取源码文件并按 [`decode_source`] 转码
+*/
+pub fn read_source(
+    path: impl AsRef<Path>,
+    encoding: SourceEncoding,
+) -> Result<String, std::io::Error> {
+    let bytes = fs::read(path)?;
+    Ok(decode_source(&bytes, encoding).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        build_index, find_parse_errors, find_text_in_file, get_identifier_at, lang::RustQuery,
+        tests::TempFile,
+    };
+
+    #[test]
+    fn test_decode_source() {
+        let auto = SourceEncoding::default();
+        assert_eq!(decode_source(b"\xef\xbb\xbfuse std;", auto), "use std;");
+        assert_eq!(decode_source(b"\xff\xfeh\x00i\x00\n\x00", auto), "hi\n");
+        assert_eq!(decode_source(b"caf\xe9", auto), "caf\u{fffd}");
+        assert_eq!("auto".parse(), Ok(auto));
+        assert!("unknown".parse::<SourceEncoding>().is_err());
+
+        let mut bytes = vec![0xff, 0xfe];
+        bytes.extend(
+            "/* 注释 */\nint answer;\n"
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes),
+        );
+        let file = TempFile::new("utf16.c", bytes);
+        let lines = find_text_in_file(file.path(), "answer", None, auto).unwrap();
+        assert_eq!(lines, vec![(2, "int answer;".to_string())]);
+    }
+
+    #[test]
+    fn test_gbk_source() {
+        let gbk: SourceEncoding = "gbk".parse().unwrap();
+        let (bytes, _, _) =
+            encoding_rs::GBK.encode("# 计算面积\ndef 面积(宽, 高):\n    return 宽 * 高\n");
+        let file = TempFile::new("gbk.py", bytes);
+
+        let lines = find_text_in_file(file.path(), "面积", None, gbk).unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                (1, "# 计算面积".to_string()),
+                (2, "def 面积(宽, 高):".to_string())
+            ]
+        );
+        // 没有 BOM 也未指定编码时按 UTF-8 读取，匹配不到
+        let lines = find_text_in_file(file.path(), "面积", None, SourceEncoding::default());
+        assert!(lines.unwrap().is_empty());
+
+        let index = build_index(Path::new(file.path()), gbk);
+        let symbols: Vec<(usize, &str)> = index
+            .iter()
+            .map(|item| (item.line, item.qualified_name.as_str()))
+            .collect();
+        assert_eq!(symbols, vec![(2, "面积")]);
+    }
+
+    #[test]
+    fn test_source_columns() {
+        let code = "fn 计算() {\n    let 面积 = ;\n}\n";
+        let (gbk, _, _) = encoding_rs::GBK.encode(code);
+        let mut utf16 = vec![0xff, 0xfe];
+        utf16.extend(code.encode_utf16().flat_map(u16::to_le_bytes));
+        for (name, bytes, encoding) in [
+            ("columns-gbk.rs", gbk.into_owned(), "gbk"),
+            ("columns-utf16.rs", utf16, "auto"),
+        ] {
+            let file = TempFile::new(name, bytes);
+            let source = read_source(file.path(), encoding.parse().unwrap()).unwrap();
+            // 语法错误与 `def` 的列都按字符计算，而不是源文件或 UTF-8 的字节
+            let errors = find_parse_errors(&source, &RustQuery);
+            let columns: Vec<_> = errors
+                .iter()
+                .map(|error| (error.line, error.column))
+                .collect();
+            assert_eq!(columns, vec![(2, 12)], "{name}");
+            assert_eq!(
+                get_identifier_at(&source, 2, 9, &RustQuery),
+                Some("面积".to_string()),
+                "{name}"
+            );
+        }
+    }
+}
@@ -0,0 +1,114 @@
+/**
+* 模糊匹配的结果
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// 匹配到的字符在名称中的字节位置
+    pub positions: Vec<usize>,
+}
+
+const MATCH_SCORE: i64 = 16;
+const CONSECUTIVE_BONUS: i64 = 8;
+const START_BONUS: i64 = 10;
+const BOUNDARY_BONUS: i64 = 6;
+const MAX_GAP_PENALTY: i64 = 6;
+
+/**
+* 按驼峰和下划线分词的模糊匹配，忽略大小写，如 `gasym`、`GAS` 可以匹配 `get_all_symbols`、`getAllSymbols`
+* 每个字符必须紧接上一个匹配的字符，或者位于单词的开头，避免零散的字符匹配
+*/
+pub fn fuzzy_match(pattern: &str, name: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+    let chars: Vec<(usize, char)> = name.char_indices().collect();
+    if pattern.is_empty() || pattern.len() > chars.len() {
+        return None;
+    }
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|(_, ch)| ch.to_lowercase().next().unwrap_or(*ch))
+        .collect();
+    let bonus: Vec<Option<i64>> = (0..chars.len())
+        .map(|index| word_start_bonus(&chars, index))
+        .collect();
+
+    // scores[i][j] 为模式的前 i + 1 个字符匹配完成、且第 i 个字符匹配名称第 j 个字符时的最高分
+    let mut scores: Vec<Vec<Option<(i64, usize)>>> = vec![vec![None; chars.len()]; pattern.len()];
+    for (i, pattern_char) in pattern.iter().enumerate() {
+        for j in i..chars.len() {
+            if lower[j] != *pattern_char {
+                continue;
+            }
+            if i == 0 {
+                scores[0][j] = bonus[j].map(|bonus| (MATCH_SCORE + bonus, j));
+                continue;
+            }
+            let mut best = None;
+            if let Some((score, _)) = j.checked_sub(1).and_then(|k| scores[i - 1][k]) {
+                best = Some((score + MATCH_SCORE + CONSECUTIVE_BONUS, j - 1));
+            }
+            if let Some(word_bonus) = bonus[j] {
+                let previous = scores[i - 1].iter().enumerate();
+                for (k, score) in previous.take(j.saturating_sub(1)).skip(i - 1) {
+                    if let Some((score, _)) = *score {
+                        let gap = ((j - k - 1) as i64).min(MAX_GAP_PENALTY);
+                        let score = score + MATCH_SCORE + word_bonus - gap;
+                        if best.is_none_or(|(best_score, _)| score > best_score) {
+                            best = Some((score, k));
+                        }
+                    }
+                }
+            }
+            scores[i][j] = best;
+        }
+    }
+
+    let last = pattern.len() - 1;
+    let (mut j, (score, _)) = scores[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| score.map(|score| (j, score)))
+        .max_by_key(|(j, (score, _))| (*score, std::cmp::Reverse(*j)))?;
+    let mut positions = vec![chars[j].0];
+    for i in (1..=last).rev() {
+        j = scores[i][j].unwrap().1;
+        positions.push(chars[j].0);
+    }
+    positions.reverse();
+    Some(FuzzyMatch { score, positions })
+}
+
+/**
+* 单词开头的加分，不是单词开头时为 None
+*/
+fn word_start_bonus(chars: &[(usize, char)], index: usize) -> Option<i64> {
+    let Some(index) = index.checked_sub(1) else {
+        return Some(START_BONUS);
+    };
+    let (prev, current) = (chars[index].1, chars[index + 1].1);
+    let is_boundary = !prev.is_alphanumeric() && current.is_alphanumeric()
+        || prev.is_lowercase() && current.is_uppercase()
+        || !prev.is_ascii_digit() && current.is_ascii_digit();
+    is_boundary.then_some(BOUNDARY_BONUS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_match() {
+        let positions = |pattern: &str, name: &str| fuzzy_match(pattern, name).map(|m| m.positions);
+        assert_eq!(
+            positions("gasym", "get_all_symbols"),
+            Some(vec![0, 4, 8, 9, 10])
+        );
+        assert_eq!(positions("GAS", "getAllSymbols"), Some(vec![0, 3, 6]));
+        assert_eq!(positions("gtall", "get_all_symbols"), None);
+        assert_eq!(positions("xyz", "get_all_symbols"), None);
+        // 连续匹配的得分高于跨单词匹配
+        let consecutive = fuzzy_match("par", "parse_args").unwrap().score;
+        let scattered = fuzzy_match("par", "put_all_rows").unwrap().score;
+        assert!(consecutive > scattered);
+    }
+}
@@ -1,10 +1,10 @@
 use tree_sitter::{Language, Node};
 
-use super::{get_defination_string, CQuery, SymbolQuery};
+use super::{get_declarator_name, get_definition_tokens, CQuery, Definition, SymbolQuery};
 
 impl SymbolQuery for CQuery {
     fn get_queries(&self) -> Vec<String> {
-        return vec![
+        vec![
             String::from(
                 r#"
             ((function_definition
@@ -19,11 +19,50 @@ impl SymbolQuery for CQuery {
             String::from(
                 r#"
                 ((struct_specifier
-                    name:(type_identifier) @struct)
+                    name:(type_identifier) @struct
+                    body:(_))
                     (#match? @struct ":?"))
                 "#,
             ),
-        ];
+            String::from(
+                r#"
+                ((enum_specifier
+                    name:(type_identifier) @enum
+                    body:(_))
+                    (#match? @enum ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ([(union_specifier name:(type_identifier) @type body:(_))
+                  (type_definition declarator:(type_identifier) @type)]
+                    (#match? @type ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ([(preproc_def name:(identifier) @macro)
+                  (preproc_function_def name:(identifier) @macro)]
+                    (#match? @macro ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ((field_declaration
+                    declarator:[(field_identifier) @field
+                                (pointer_declarator declarator:(field_identifier) @field)
+                                (array_declarator declarator:(field_identifier) @field)])
+                    (#match? @field ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ((enumerator
+                    name:(identifier) @variant)
+                    (#match? @variant ":?"))
+                "#,
+            ),
+        ]
     }
 
     fn get_lang(&self) -> Language {
@@ -32,18 +71,73 @@ impl SymbolQuery for CQuery {
 
     fn is_key_node(&self, node: &Node) -> bool {
         match node.kind() {
-            "function_definition" => true,
+            "function_definition"
+            | "type_definition"
+            | "preproc_def"
+            | "preproc_function_def"
+            | "field_declaration"
+            | "enumerator" => true,
+            // 只有带定义体的才是声明，`struct Point p` 只是引用
+            "struct_specifier" | "union_specifier" | "enum_specifier" => {
+                node.child_by_field_name("body").is_some()
+                    && node
+                        .parent()
+                        .is_some_and(|parent| parent.kind() != "type_definition")
+            }
+            // 全局变量和函数原型
+            "declaration" => node
+                .parent()
+                .is_some_and(|parent| parent.kind() == "translation_unit"),
             _ => false,
         }
     }
 
-    fn get_definition(&self, code: &String, node: &Node) -> String {
+    fn get_definition(&self, code: &str, node: &Node) -> Definition {
         let node_type = node.kind();
-        let definition_list = vec![("function_definition", "compound_statement")];
+        let definition_list = vec![
+            ("function_definition", "compound_statement"),
+            ("struct_specifier", "field_declaration_list"),
+            ("union_specifier", "field_declaration_list"),
+            ("enum_specifier", "enumerator_list"),
+            ("type_definition", ";"),
+            ("declaration", ";"),
+            ("field_declaration", ";"),
+            ("enumerator", ""),
+            ("preproc_def", ""),
+            ("preproc_function_def", ""),
+        ];
         let keywords = vec![
-            "struct", "int", "char", "void", "float", "double", "long", "unsigned", "signed",
+            "struct", "union", "enum", "typedef", "static", "extern", "const", "#define", "int",
+            "char", "void", "float", "double", "long", "unsigned", "signed",
         ];
 
-        return get_defination_string(definition_list, keywords, code, node_type, node);
+        get_definition_tokens(definition_list, keywords, code, node_type, node)
+    }
+
+    fn get_container<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            "struct_specifier" | "union_specifier" | "enum_specifier" => {
+                node.child_by_field_name("name")
+            }
+            "type_definition" => get_declarator_name(node),
+            _ => None,
+        }
+    }
+
+    fn get_identifier_kinds(&self) -> Vec<&'static str> {
+        vec!["identifier", "type_identifier", "field_identifier"]
+    }
+
+    fn get_symbol_name<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            "function_definition" | "declaration" | "type_definition" | "field_declaration" => {
+                get_declarator_name(node)
+            }
+            _ => node.child_by_field_name("name"),
+        }
+    }
+
+    fn get_import_kinds(&self) -> Vec<&'static str> {
+        vec!["preproc_include"]
     }
 }
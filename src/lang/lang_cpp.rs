@@ -1,10 +1,10 @@
 use tree_sitter::{Language, Node};
 
-use super::{get_defination_string, CppQuery, SymbolQuery};
+use super::{get_declarator_name, get_definition_tokens, CppQuery, Definition, SymbolQuery};
 
 impl SymbolQuery for CppQuery {
     fn get_queries(&self) -> Vec<String> {
-        return vec![
+        vec![
             String::from(
                 r#"
             ((function_definition
@@ -16,21 +16,87 @@ impl SymbolQuery for CppQuery {
             (#match? @function ":?"))
             "#,
             ),
+            String::from(
+                r#"
+            ((function_definition
+                declarator:(
+                    function_declarator
+                        declarator:[(field_identifier) (qualified_identifier)] @method
+                )
+            )
+            (#match? @method ":?"))
+            "#,
+            ),
             String::from(
                 r#"
                 ((struct_specifier
-                    name:(type_identifier) @struct)
+                    name:(type_identifier) @struct
+                    body:(_))
                     (#match? @struct ":?"))
                 "#,
             ),
             String::from(
                 r#"
                 ((class_specifier
-                    name:(type_identifier) @class)
+                    name:(type_identifier) @class
+                    body:(_))
                     (#match? @class ":?"))
                 "#,
             ),
-        ];
+            String::from(
+                r#"
+                ((enum_specifier
+                    name:(type_identifier) @enum
+                    body:(_))
+                    (#match? @enum ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ([(union_specifier name:(type_identifier) @type body:(_))
+                  (type_definition declarator:(type_identifier) @type)
+                  (alias_declaration name:(type_identifier) @type)]
+                    (#match? @type ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ((namespace_definition
+                    name:(namespace_identifier) @module)
+                    (#match? @module ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ([(preproc_def name:(identifier) @macro)
+                  (preproc_function_def name:(identifier) @macro)]
+                    (#match? @macro ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ((field_declaration
+                    declarator:[(field_identifier) @field
+                                (pointer_declarator declarator:(field_identifier) @field)
+                                (array_declarator declarator:(field_identifier) @field)])
+                    (#match? @field ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ((enumerator
+                    name:(identifier) @variant)
+                    (#match? @variant ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ((field_declaration
+                    declarator:(function_declarator declarator:(field_identifier) @method))
+                    (#match? @method ":?"))
+                "#,
+            ),
+        ]
     }
 
     fn get_lang(&self) -> Language {
@@ -40,24 +106,53 @@ impl SymbolQuery for CppQuery {
     fn is_key_node(&self, node: &Node) -> bool {
         match node.kind() {
             "function_definition"
-            | "struct_specifier"
-            | "class_specifier"
-            | "field_declaration" => true,
+            | "field_declaration"
+            | "namespace_definition"
+            | "type_definition"
+            | "alias_declaration"
+            | "preproc_def"
+            | "preproc_function_def"
+            | "enumerator" => true,
+            // 只有带定义体的才是声明，`struct Point p` 只是引用
+            "struct_specifier" | "class_specifier" | "union_specifier" | "enum_specifier" => {
+                node.child_by_field_name("body").is_some()
+                    && node
+                        .parent()
+                        .is_some_and(|parent| parent.kind() != "type_definition")
+            }
+            // 全局变量和函数原型
+            "declaration" => node.parent().is_some_and(|parent| {
+                matches!(parent.kind(), "translation_unit" | "declaration_list")
+            }),
             _ => false,
         }
     }
 
-    fn get_definition(&self, code: &String, node: &Node) -> String {
+    fn get_definition(&self, code: &str, node: &Node) -> Definition {
         let node_type = node.kind();
         let definition_list = vec![
             ("function_definition", "compound_statement"),
             ("struct_specifier", "field_declaration_list"),
             ("class_specifier", "field_declaration_list"),
+            ("union_specifier", "field_declaration_list"),
+            ("enum_specifier", "enumerator_list"),
+            ("namespace_definition", "declaration_list"),
             ("field_declaration", ";"),
+            ("declaration", ";"),
+            ("type_definition", ";"),
+            ("alias_declaration", ";"),
+            ("enumerator", ""),
+            ("preproc_def", ""),
+            ("preproc_function_def", ""),
         ];
         let keywords = vec![
             "struct",
             "class",
+            "union",
+            "enum",
+            "namespace",
+            "typedef",
+            "using",
             "public",
             "private",
             "protected",
@@ -65,8 +160,54 @@ impl SymbolQuery for CppQuery {
             "static",
             "const",
             "void",
+            "#define",
         ];
 
-        return get_defination_string(definition_list, keywords, code, node_type, node);
+        get_definition_tokens(definition_list, keywords, code, node_type, node)
+    }
+
+    fn get_container<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            "namespace_definition"
+            | "class_specifier"
+            | "struct_specifier"
+            | "union_specifier"
+            | "enum_specifier" => node.child_by_field_name("name"),
+            "type_definition" => get_declarator_name(node),
+            _ => None,
+        }
+    }
+
+    fn is_type_container(&self, node: &Node) -> bool {
+        matches!(
+            node.kind(),
+            "class_specifier" | "struct_specifier" | "union_specifier"
+        )
+    }
+
+    fn get_separator(&self) -> &'static str {
+        "::"
+    }
+
+    fn get_identifier_kinds(&self) -> Vec<&'static str> {
+        vec![
+            "identifier",
+            "type_identifier",
+            "field_identifier",
+            "namespace_identifier",
+        ]
+    }
+
+    fn get_symbol_name<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            "function_definition" | "field_declaration" | "declaration" | "type_definition" => {
+                get_declarator_name(node)
+            }
+            _ => node.child_by_field_name("name"),
+        }
+    }
+
+    fn get_import_kinds(&self) -> Vec<&'static str> {
+        vec!["preproc_include", "using_declaration"]
     }
 }
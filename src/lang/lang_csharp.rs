@@ -1,10 +1,10 @@
 use tree_sitter::{Language, Node};
 
-use super::{get_defination_string, CSharpQuery, SymbolQuery};
+use super::{get_definition_tokens, CSharpQuery, Definition, SymbolQuery};
 
 impl SymbolQuery for CSharpQuery {
     fn get_queries(&self) -> Vec<String> {
-        return vec![
+        vec![
             String::from(
                 r#"
             ((struct_declaration
@@ -14,19 +14,61 @@ impl SymbolQuery for CSharpQuery {
             ),
             String::from(
                 r#"
-            ((class_declaration
-             name:(identifier) @class)
+            ([(class_declaration name:(identifier) @class)
+              (record_declaration name:(identifier) @class)]
              (#match? @class ":?"))
             "#,
             ),
             String::from(
                 r#"
-            ((method_declaration
-             name:(identifier) @method)
+            ((interface_declaration
+             name:(identifier) @interface)
+             (#match? @interface ":?"))
+            "#,
+            ),
+            String::from(
+                r#"
+            ((enum_declaration
+             name:(identifier) @enum)
+             (#match? @enum ":?"))
+            "#,
+            ),
+            String::from(
+                r#"
+            ((delegate_declaration
+             name:(identifier) @type)
+             (#match? @type ":?"))
+            "#,
+            ),
+            String::from(
+                r#"
+            ([(method_declaration name:(identifier) @method)
+              (constructor_declaration name:(identifier) @method)]
              (#match? @method ":?"))
             "#,
             ),
-        ];
+            String::from(
+                r#"
+            ([(namespace_declaration name:(_) @module)
+              (file_scoped_namespace_declaration name:(_) @module)]
+             (#match? @module ":?"))
+            "#,
+            ),
+            String::from(
+                r#"
+            ([(field_declaration (variable_declaration (variable_declarator . (identifier) @field)))
+              (property_declaration name:(identifier) @field)]
+             (#match? @field ":?"))
+            "#,
+            ),
+            String::from(
+                r#"
+            ((enum_member_declaration
+             name:(identifier) @variant)
+             (#match? @variant ":?"))
+            "#,
+            ),
+        ]
     }
 
     fn get_lang(&self) -> Language {
@@ -34,34 +76,111 @@ impl SymbolQuery for CSharpQuery {
     }
 
     fn is_key_node(&self, node: &Node) -> bool {
-        match node.kind() {
+        matches!(
+            node.kind(),
             "struct_declaration"
-            | "class_declaration"
-            | "method_declaration"
-            | "namespace_declaration" => true,
-            _ => false,
-        }
+                | "class_declaration"
+                | "record_declaration"
+                | "interface_declaration"
+                | "enum_declaration"
+                | "delegate_declaration"
+                | "method_declaration"
+                | "constructor_declaration"
+                | "property_declaration"
+                | "field_declaration"
+                | "enum_member_declaration"
+                | "namespace_declaration"
+                | "file_scoped_namespace_declaration"
+        )
     }
 
-    fn get_definition(&self, code: &String, node: &Node) -> String {
+    fn get_definition(&self, code: &str, node: &Node) -> Definition {
         let node_type = node.kind();
         let definition_list = vec![
             ("namespace_declaration", "declaration_list"),
+            ("file_scoped_namespace_declaration", ";"),
             ("struct_declaration", "declaration_list"),
             ("class_declaration", "declaration_list"),
-            ("method_declaration", "parameter_list"),
+            ("record_declaration", "declaration_list"),
+            ("interface_declaration", "declaration_list"),
+            ("enum_declaration", "enum_member_declaration_list"),
+            ("delegate_declaration", ";"),
+            ("method_declaration", "block"),
+            ("constructor_declaration", "block"),
+            ("property_declaration", "accessor_list"),
+            ("field_declaration", ";"),
+            ("enum_member_declaration", ""),
         ];
         let keywords = vec![
+            "namespace",
             "struct",
             "class",
+            "record",
+            "interface",
+            "enum",
+            "delegate",
             "public",
             "private",
             "protected",
             "internal",
             "static",
+            "const",
+            "readonly",
+            "abstract",
+            "virtual",
+            "override",
+            "async",
             "void",
         ];
 
-        return get_defination_string(definition_list, keywords, code, node_type, node);
+        get_definition_tokens(definition_list, keywords, code, node_type, node)
+    }
+
+    fn get_container<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            "namespace_declaration"
+            | "file_scoped_namespace_declaration"
+            | "class_declaration"
+            | "record_declaration"
+            | "struct_declaration"
+            | "interface_declaration"
+            | "enum_declaration" => node.child_by_field_name("name"),
+            _ => None,
+        }
+    }
+
+    fn get_symbol_name<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            // 字段名称在 variable_declaration 的第一个 variable_declarator 中
+            "field_declaration" => node
+                .named_children(&mut node.walk())
+                .find(|child| child.kind() == "variable_declaration")?
+                .named_children(&mut node.walk())
+                .find(|child| child.kind() == "variable_declarator")?
+                .named_child(0),
+            _ => node.child_by_field_name("name"),
+        }
+    }
+
+    fn get_import_kinds(&self) -> Vec<&'static str> {
+        vec!["using_directive"]
+    }
+
+    fn get_call_kinds(&self) -> Vec<&'static str> {
+        vec!["invocation_expression"]
+    }
+
+    fn get_callee<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        let mut callee = node.child_by_field_name("function")?;
+        loop {
+            callee = match callee.kind() {
+                "identifier" => return Some(callee),
+                // 泛型方法 `Parse<T>()` 的名称是 generic_name 的第一个子节点
+                "generic_name" => callee.named_child(0)?,
+                "member_access_expression" => callee.child_by_field_name("name")?,
+                "invocation_expression" => callee.child_by_field_name("function")?,
+                _ => return None,
+            };
+        }
     }
 }
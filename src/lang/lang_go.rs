@@ -1,11 +1,10 @@
-use colored::Colorize;
 use tree_sitter::{Language, Node};
 
-use super::{get_defination_string, GoQuery, SymbolQuery};
+use super::{get_definition_tokens, Definition, GoQuery, SymbolQuery, TokenKind};
 
 impl SymbolQuery for GoQuery {
     fn get_queries(&self) -> Vec<String> {
-        return vec![
+        vec![
             String::from(
                 r#"
             ((function_declaration
@@ -13,14 +12,71 @@ impl SymbolQuery for GoQuery {
              (#match? @function ":?"))
             "#,
             ),
+            String::from(
+                r#"
+            ((method_declaration
+             name:(field_identifier) @method)
+             (#match? @method ":?"))
+            "#,
+            ),
             String::from(
                 r#"
             ((type_spec
-             name:(type_identifier) @type)
+             name:(type_identifier) @struct
+             type:(struct_type))
+             (#match? @struct ":?"))
+            "#,
+            ),
+            String::from(
+                r#"
+            ((type_spec
+             name:(type_identifier) @interface
+             type:(interface_type))
+             (#match? @interface ":?"))
+            "#,
+            ),
+            String::from(
+                r#"
+            ((type_spec
+             name:(type_identifier) @type
+             type:[
+                (type_identifier)
+                (qualified_type)
+                (pointer_type)
+                (slice_type)
+                (array_type)
+                (map_type)
+                (channel_type)
+                (function_type)
+                (generic_type)
+                (negated_type)
+                (parenthesized_type)
+             ])
              (#match? @type ":?"))
             "#,
             ),
-        ];
+            String::from(
+                r#"
+            ((method_elem
+             name:(field_identifier) @method)
+             (#match? @method ":?"))
+            "#,
+            ),
+            String::from(
+                r#"
+            ((field_declaration
+             name:(field_identifier) @field)
+             (#match? @field ":?"))
+            "#,
+            ),
+            String::from(
+                r#"
+            ((source_file
+             (const_declaration (const_spec name:(identifier) @constant)))
+             (#match? @constant ":?"))
+            "#,
+            ),
+        ]
     }
 
     fn get_lang(&self) -> Language {
@@ -28,64 +84,126 @@ impl SymbolQuery for GoQuery {
     }
 
     fn is_key_node(&self, node: &Node) -> bool {
-        match node.kind() {
+        matches!(
+            node.kind(),
             "function_declaration"
-            | "method_declaration"
-            | "method_elem"
-            | "type_declaration"
-            | "field_declaration" => true,
-            _ => false,
-        }
+                | "method_declaration"
+                | "method_elem"
+                | "type_declaration"
+                | "field_declaration"
+        ) || is_package_level_spec(node)
     }
 
-    fn get_definition(&self, code: &String, node: &Node) -> String {
+    fn get_definition(&self, code: &str, node: &Node) -> Definition {
         let node_type = node.kind();
         let definition_list = vec![
-            ("function_declaration", "parameter_list"),
-            ("method_elem", "parameter_list"),
+            ("function_declaration", "block"),
+            ("method_declaration", "block"),
+            ("method_elem", ""),
         ];
         let keywords = vec!["func", "type"];
 
-        let mut output = String::new();
+        let mut definition = Definition::default();
 
         if node_type == "type_declaration" {
+            definition.push(TokenKind::Keyword, "type");
             for child in node.children(&mut node.walk()) {
-                if child.kind() == "type" {
-                    output.push_str("type".purple().to_string().as_str())
-                }
                 if child.kind() == "type_spec" {
+                    let name_node = child.child_by_field_name("name");
                     for sub_child in child.children(&mut child.walk()) {
                         if sub_child.kind() == "struct_type" {
-                            output.push_str("struct".purple().to_string().as_str())
+                            definition.push(TokenKind::Keyword, "struct");
                         } else if sub_child.kind() == "interface_type" {
-                            output.push_str("interface".purple().to_string().as_str())
+                            definition.push(TokenKind::Keyword, "interface");
+                        } else if Some(sub_child) == name_node {
+                            definition.push(TokenKind::Name, &code[sub_child.byte_range()]);
                         } else {
-                            output.push_str(&code[sub_child.byte_range()]);
+                            definition.push(TokenKind::Text, &code[sub_child.byte_range()]);
                         }
-                        output.push(' ')
                     }
                 }
-                output.push(' ')
             }
-            return output;
-        } else if node.kind() == "method_declaration" {
+            definition
+        } else if node.kind() == "field_declaration" {
+            // 同一行声明的多个字段，如 `Width, Height float64`
+            let names: Vec<&str> = node
+                .children_by_field_name("name", &mut node.walk())
+                .map(|name| &code[name.byte_range()])
+                .collect();
+            if !names.is_empty() {
+                definition.push(TokenKind::Name, &names.join(", "));
+            }
+            if let Some(type_node) = node.child_by_field_name("type") {
+                definition.push(TokenKind::Text, &code[type_node.byte_range()]);
+            }
+            definition
+        } else if is_package_level_spec(node) {
+            let keyword = match node.kind() {
+                "const_spec" => "const",
+                _ => "var",
+            };
+            definition.push(TokenKind::Keyword, keyword);
             for child in node.children(&mut node.walk()) {
-                if child.kind() == "func" {
-                    output.push_str("func".purple().to_string().as_str())
-                } else if child.kind() == "block" {
-                    break;
+                let text = &code[child.byte_range()];
+                if node.child_by_field_name("name") == Some(child) {
+                    definition.push(TokenKind::Name, text);
                 } else {
-                    output.push_str(&code[child.byte_range()]);
+                    definition.push_words(text, &keywords);
                 }
-                output.push(' ')
             }
-            return output;
-        } else if node.kind() == "field_declaration" {
-            let id_node = node.child_by_field_name("name").unwrap();
-            output.push_str(&code[id_node.byte_range()]);
-            return output;
+            definition
         } else {
-            return get_defination_string(definition_list, keywords, code, node_type, node);
+            get_definition_tokens(definition_list, keywords, code, node_type, node)
         }
     }
+
+    fn get_container<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            // 方法以接收者类型作为容器，如 func (r *Rectangle) Area()
+            "method_declaration" => {
+                let receiver = node.child_by_field_name("receiver")?;
+                let parameter = receiver.named_child(0)?;
+                let mut receiver_type = parameter.child_by_field_name("type")?;
+                if receiver_type.kind() == "pointer_type" {
+                    receiver_type = receiver_type.named_child(0)?;
+                }
+                Some(receiver_type)
+            }
+            "type_spec" => node.child_by_field_name("name"),
+            _ => None,
+        }
+    }
+
+    fn get_identifier_kinds(&self) -> Vec<&'static str> {
+        vec![
+            "identifier",
+            "type_identifier",
+            "field_identifier",
+            "package_identifier",
+        ]
+    }
+
+    fn get_symbol_name<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            "type_declaration" => node
+                .named_child(0)
+                .and_then(|type_spec| type_spec.child_by_field_name("name")),
+            _ => node.child_by_field_name("name"),
+        }
+    }
+
+    fn get_import_kinds(&self) -> Vec<&'static str> {
+        vec!["import_declaration"]
+    }
+}
+
+/**
+ * 包级别的常量和变量，`const (...)` 分组中的每一项单独作为大纲节点
+ */
+fn is_package_level_spec(node: &Node) -> bool {
+    matches!(node.kind(), "const_spec" | "var_spec")
+        && node
+            .parent()
+            .and_then(|declaration| declaration.parent())
+            .is_some_and(|parent| parent.kind() == "source_file")
 }
@@ -1,10 +1,10 @@
 use tree_sitter::{Language, Node};
 
-use super::{get_defination_string, JavaQuery, SymbolQuery};
+use super::{get_definition_tokens, Definition, JavaQuery, SymbolQuery};
 
 impl SymbolQuery for JavaQuery {
     fn get_queries(&self) -> Vec<String> {
-        return vec![
+        vec![
             String::from(
                 r#"
             ((method_declaration
@@ -21,12 +21,54 @@ impl SymbolQuery for JavaQuery {
             ),
             String::from(
                 r#"
-                ((interface_declaration
-                    name:(identifier) @interface)
+                ([(interface_declaration name:(identifier) @interface)
+                  (annotation_type_declaration name:(identifier) @interface)]
                     (#match? @interface ":?"))
                 "#,
             ),
-        ];
+            String::from(
+                r#"
+                ((enum_declaration
+                    name:(identifier) @enum)
+                    (#match? @enum ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ((record_declaration
+                    name:(identifier) @class)
+                    (#match? @class ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ([(constructor_declaration name:(identifier) @method)
+                  (compact_constructor_declaration name:(identifier) @method)]
+                    (#match? @method ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ((field_declaration
+                    declarator:(variable_declarator name:(identifier) @field))
+                    (#match? @field ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ((constant_declaration
+                    declarator:(variable_declarator name:(identifier) @constant))
+                    (#match? @constant ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ((enum_constant
+                    name:(identifier) @variant)
+                    (#match? @variant ":?"))
+                "#,
+            ),
+        ]
     }
 
     fn get_lang(&self) -> Language {
@@ -34,31 +76,100 @@ impl SymbolQuery for JavaQuery {
     }
 
     fn is_key_node(&self, node: &Node) -> bool {
-        match node.kind() {
-            "class_declaration" | "method_declaration" | "interface_declaration" => true,
-            _ => false,
-        }
+        matches!(
+            node.kind(),
+            "class_declaration"
+                | "method_declaration"
+                | "interface_declaration"
+                | "enum_declaration"
+                | "record_declaration"
+                | "annotation_type_declaration"
+                | "constructor_declaration"
+                | "compact_constructor_declaration"
+                | "field_declaration"
+                | "constant_declaration"
+                | "enum_constant"
+                | "annotation_type_element_declaration"
+        )
     }
 
-    fn get_definition(&self, code: &String, node: &Node) -> String {
+    fn get_definition(&self, code: &str, node: &Node) -> Definition {
         let node_type = node.kind();
         // 0: 类型的根节点， 1: 结束节点
         let definition_list = vec![
             ("class_declaration", "class_body"),
-            ("method_declaration", "formal_parameters"),
+            ("method_declaration", "block"),
             ("interface_declaration", "interface_body"),
+            ("enum_declaration", "enum_body"),
+            ("record_declaration", "class_body"),
+            ("annotation_type_declaration", "annotation_type_body"),
+            ("constructor_declaration", "constructor_body"),
+            ("compact_constructor_declaration", "block"),
+            ("field_declaration", ";"),
+            ("constant_declaration", ";"),
+            ("enum_constant", ""),
+            ("annotation_type_element_declaration", ";"),
         ];
         // 语言关键字，需要高亮展示
         let keywords = vec![
             "static",
+            "final",
+            "abstract",
             "class",
             "extends",
+            "implements",
             "public",
             "private",
             "protected",
             "interface",
+            "@interface",
+            "enum",
+            "record",
         ];
 
-        return get_defination_string(definition_list, keywords, code, node_type, node);
+        get_definition_tokens(definition_list, keywords, code, node_type, node)
+    }
+
+    fn get_container<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            "class_declaration"
+            | "interface_declaration"
+            | "enum_declaration"
+            | "record_declaration" => node.child_by_field_name("name"),
+            // 包名作为最外层的容器
+            "program" => node
+                .children(&mut node.walk())
+                .find(|child| child.kind() == "package_declaration")
+                .and_then(|package| package.named_child(0)),
+            _ => None,
+        }
+    }
+
+    fn get_identifier_kinds(&self) -> Vec<&'static str> {
+        vec!["identifier", "type_identifier"]
+    }
+
+    fn get_symbol_name<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            "field_declaration" | "constant_declaration" => node
+                .child_by_field_name("declarator")
+                .and_then(|declarator| declarator.child_by_field_name("name")),
+            _ => node.child_by_field_name("name"),
+        }
+    }
+
+    fn get_import_kinds(&self) -> Vec<&'static str> {
+        vec!["import_declaration", "package_declaration"]
+    }
+
+    fn get_call_kinds(&self) -> Vec<&'static str> {
+        vec!["method_invocation", "object_creation_expression"]
+    }
+
+    fn get_callee<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            "object_creation_expression" => node.child_by_field_name("type"),
+            _ => node.child_by_field_name("name"),
+        }
     }
 }
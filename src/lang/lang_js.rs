@@ -1,41 +1,174 @@
 use tree_sitter::{Language, Node};
 
-use super::{get_defination_string, JavascriptQuery, SymbolQuery};
+use super::{get_definition_tokens, Definition, JavascriptQuery, SymbolQuery, TokenKind};
 
 impl SymbolQuery for JavascriptQuery {
     fn get_queries(&self) -> Vec<String> {
-        return vec![String::from(
-            r#"
-            ((function_declaration
-             name:(identifier) @function)
+        vec![
+            String::from(
+                r#"
+            ([(function_declaration name:(identifier) @function)
+              (generator_function_declaration name:(identifier) @function)]
              (#match? @function ":?"))
             "#,
-        )];
+            ),
+            String::from(
+                r#"
+            ((variable_declarator
+             name:(identifier) @function
+             value:[(arrow_function) (function_expression)])
+             (#match? @function ":?"))
+            "#,
+            ),
+            String::from(
+                r#"
+            ((class_declaration
+             name:(identifier) @class)
+             (#match? @class ":?"))
+            "#,
+            ),
+            String::from(
+                r#"
+            ((method_definition
+             name:(property_identifier) @method)
+             (#match? @method ":?"))
+            "#,
+            ),
+            String::from(
+                r#"
+            ((field_definition
+             property:(property_identifier) @field)
+             (#match? @field ":?"))
+            "#,
+            ),
+            String::from(
+                r#"
+            ([(program (lexical_declaration "const" (variable_declarator name:(identifier) @constant value:(_) @_value)))
+              (program (export_statement (lexical_declaration "const" (variable_declarator name:(identifier) @constant value:(_) @_value))))]
+             (#not-match? @_value "=>|^(async )?function")
+             (#match? @constant ":?"))
+            "#,
+            ),
+        ]
     }
 
     fn get_lang(&self) -> Language {
-        tree_sitter_javascript::language()
+        tree_sitter_javascript::LANGUAGE.into()
     }
 
     fn is_key_node(&self, node: &Node) -> bool {
         match node.kind() {
             "function_declaration"
+            | "generator_function_declaration"
             | "class_declaration"
             | "method_definition"
-            | "lexical_declaration" => true,
+            | "field_definition" => true,
+            // 只有顶层的变量声明作为大纲节点，函数内的局部变量不展示
+            "lexical_declaration" | "variable_declaration" => node
+                .parent()
+                .is_some_and(|parent| matches!(parent.kind(), "program" | "export_statement")),
             _ => false,
         }
     }
 
-    fn get_definition(&self, code: &String, node: &Node) -> String {
+    fn get_definition(&self, code: &str, node: &Node) -> Definition {
         let node_type = node.kind();
+        let keywords = vec![
+            "function",
+            "function*",
+            "async",
+            "const",
+            "let",
+            "var",
+            "class",
+            "extends",
+            "static",
+            "get",
+            "set",
+        ];
+        if matches!(node_type, "lexical_declaration" | "variable_declaration") {
+            return get_variable_definition(code, node, &keywords);
+        }
         let definition_list = vec![
-            ("function_declaration", "formal_parameters"),
+            ("function_declaration", "statement_block"),
+            ("generator_function_declaration", "statement_block"),
             ("class_declaration", "class_body"),
-            ("method_definition", "formal_parameters"),
+            ("method_definition", "statement_block"),
+            ("field_definition", "="),
         ];
-        let keywords = vec!["function", "async", "const", "let", "var", "class"];
 
-        return get_defination_string(definition_list, keywords, code, node_type, node);
+        get_definition_tokens(definition_list, keywords, code, node_type, node)
+    }
+
+    fn get_container<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            "class_declaration" | "class" => node.child_by_field_name("name"),
+            _ => None,
+        }
+    }
+
+    fn get_identifier_kinds(&self) -> Vec<&'static str> {
+        vec!["identifier", "property_identifier"]
+    }
+
+    fn get_symbol_name<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            "lexical_declaration" | "variable_declaration" => node
+                .named_child(0)
+                .and_then(|declarator| declarator.child_by_field_name("name")),
+            "field_definition" => node.child_by_field_name("property"),
+            _ => node.child_by_field_name("name"),
+        }
+    }
+
+    fn get_import_kinds(&self) -> Vec<&'static str> {
+        vec!["import_statement"]
+    }
+
+    fn get_call_kinds(&self) -> Vec<&'static str> {
+        vec!["call_expression", "new_expression"]
+    }
+
+    fn get_callee<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        let mut callee = node
+            .child_by_field_name("function")
+            .or_else(|| node.child_by_field_name("constructor"))?;
+        while !matches!(callee.kind(), "identifier" | "property_identifier") {
+            // `f(x)()` 取内层调用的函数，`obj.method()` 取属性名，`obj[key]()` 等没有名称
+            callee = match callee.kind() {
+                "call_expression" => callee.child_by_field_name("function")?,
+                "member_expression" => callee.child_by_field_name("property")?,
+                _ => return None,
+            };
+        }
+        Some(callee)
+    }
+}
+
+/**
+* 变量声明的定义，值为函数时展示参数，其余的值不展示
+*/
+fn get_variable_definition(code: &str, node: &Node, keywords: &[&str]) -> Definition {
+    let mut definition = Definition::default();
+    if let Some(kind) = node.child(0) {
+        definition.push_words(&code[kind.byte_range()], keywords);
+    }
+    let Some(declarator) = node.named_child(0) else {
+        return definition;
+    };
+    if let Some(name) = declarator.child_by_field_name("name") {
+        definition.push(TokenKind::Name, &code[name.byte_range()]);
+    }
+    let function = declarator
+        .child_by_field_name("value")
+        .filter(|value| matches!(value.kind(), "arrow_function" | "function_expression"));
+    if let Some(function) = function {
+        let parameters = function
+            .child_by_field_name("parameters")
+            .or_else(|| function.child_by_field_name("parameter"));
+        if let Some(parameters) = parameters {
+            definition.push(TokenKind::Params, &code[parameters.byte_range()]);
+        }
     }
+    definition
 }
@@ -0,0 +1,79 @@
+use tree_sitter::{Language, Node};
+
+use super::{get_index_segment, get_value_preview, Definition, JsonQuery, SymbolQuery, TokenKind};
+
+impl SymbolQuery for JsonQuery {
+    fn get_queries(&self) -> Vec<String> {
+        vec![String::from(
+            r#"
+            ((pair
+             key:(string (string_content) @key))
+             (#match? @key ":?"))
+            "#,
+        )]
+    }
+
+    fn get_lang(&self) -> Language {
+        tree_sitter_json::LANGUAGE.into()
+    }
+
+    fn is_key_node(&self, node: &Node) -> bool {
+        // 数组中的对象作为大纲节点，以下标区分
+        node.kind() == "pair"
+            || (node.kind() == "object" && node.parent().is_some_and(|p| p.kind() == "array"))
+    }
+
+    fn get_definition(&self, code: &str, node: &Node) -> Definition {
+        let mut definition = Definition::default();
+        if node.kind() == "object" {
+            definition.push(
+                TokenKind::Name,
+                &get_index_segment(node).unwrap_or_default(),
+            );
+            return definition;
+        }
+        let Some(name) = self.get_symbol_name(node) else {
+            return definition;
+        };
+        let key = &code[name.byte_range()];
+        match node.child_by_field_name("value") {
+            Some(value) if !matches!(value.kind(), "object" | "array") => {
+                definition.push(TokenKind::Name, &format!("{key}:"));
+                definition.push(TokenKind::Text, &get_value_preview(code, &value));
+            }
+            _ => definition.push(TokenKind::Name, key),
+        }
+        definition
+    }
+
+    fn get_container<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            "pair" => self.get_symbol_name(node),
+            _ => None,
+        }
+    }
+
+    fn get_path_segment(&self, code: &str, node: &Node) -> Option<String> {
+        if node.parent().is_some_and(|parent| parent.kind() == "array") {
+            return get_index_segment(node);
+        }
+        self.get_container(node)
+            .map(|name| code[name.byte_range()].to_string())
+    }
+
+    fn get_identifier_kinds(&self) -> Vec<&'static str> {
+        vec![]
+    }
+
+    fn get_symbol_name<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        node.child_by_field_name("key")?.named_child(0)
+    }
+
+    fn get_import_kinds(&self) -> Vec<&'static str> {
+        vec![]
+    }
+
+    fn get_call_kinds(&self) -> Vec<&'static str> {
+        vec![]
+    }
+}
@@ -0,0 +1,199 @@
+use tree_sitter::{Language, Node};
+
+use super::{get_node_end_line, Definition, MarkdownQuery, SymbolQuery, TokenKind};
+
+impl SymbolQuery for MarkdownQuery {
+    fn get_queries(&self) -> Vec<String> {
+        vec![
+            String::from(
+                r#"
+            ((atx_heading
+             heading_content:(inline) @heading)
+             (#match? @heading ":?"))
+            "#,
+            ),
+            String::from(
+                r#"
+            ((setext_heading
+             heading_content:(paragraph (inline) @heading))
+             (#match? @heading ":?"))
+            "#,
+            ),
+        ]
+    }
+
+    fn get_lang(&self) -> Language {
+        tree_sitter_md::LANGUAGE.into()
+    }
+
+    fn is_key_node(&self, node: &Node) -> bool {
+        get_heading_level(node).is_some()
+    }
+
+    fn get_definition(&self, code: &str, node: &Node) -> Definition {
+        let mut definition = Definition::default();
+        let heading = match node.kind() {
+            "section" => node.named_child(0),
+            _ => Some(*node),
+        };
+        let Some(heading) = heading else {
+            return definition;
+        };
+        // setext 标题按级别换算为对应的 `#`
+        let marker = match heading.kind() {
+            "setext_heading" => get_heading_level(&heading).map(|level| "#".repeat(level)),
+            _ => heading
+                .child(0)
+                .map(|marker| code[marker.byte_range()].trim().to_string()),
+        };
+        if let Some(marker) = marker {
+            definition.push(TokenKind::Keyword, &marker);
+        }
+        if let Some(content) = heading.child_by_field_name("heading_content") {
+            definition.push(TokenKind::Name, code[content.byte_range()].trim());
+        }
+        definition
+    }
+
+    fn get_container<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        if self.is_key_node(node) {
+            self.get_symbol_name(node)
+        } else {
+            None
+        }
+    }
+
+    fn get_separator(&self) -> &'static str {
+        " > "
+    }
+
+    fn get_parent<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        // 标题的上一级是之前最近的更高级标题，setext 标题在语法树中可能嵌套在更低级的 section 中
+        let Some(level) = get_heading_level(node) else {
+            return node.parent();
+        };
+        let mut current = *node;
+        while let Some(prev) = get_prev_block(&current) {
+            if get_heading_level(&prev).is_some_and(|prev_level| prev_level < level) {
+                return Some(prev);
+            }
+            current = prev;
+        }
+        None
+    }
+
+    fn get_end_line(&self, node: &Node) -> usize {
+        // 标题延续到下一个同级或更高级的标题之前，section 中的 setext 标题也会结束 section
+        let Some(level) = get_heading_level(node) else {
+            return get_node_end_line(node);
+        };
+        let start = match node.kind() {
+            "section" => node.named_child(0).unwrap_or(*node),
+            _ => *node,
+        };
+        let mut next = get_next_block(&start);
+        while let Some(current) = next {
+            if get_heading_level(&current).is_some_and(|next_level| next_level <= level) {
+                return current.start_position().row;
+            }
+            next = match current.kind() {
+                "section" => current.named_child(0),
+                _ => get_next_block(&current),
+            };
+        }
+        let mut root = *node;
+        while let Some(parent) = root.parent() {
+            root = parent;
+        }
+        get_node_end_line(&root)
+    }
+
+    fn get_identifier_kinds(&self) -> Vec<&'static str> {
+        vec![]
+    }
+
+    fn get_symbol_name<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        let heading = match node.kind() {
+            "section" => node.named_child(0)?,
+            _ => *node,
+        };
+        let content = heading.child_by_field_name("heading_content")?;
+        match content.kind() {
+            "paragraph" => content.named_child(0),
+            _ => Some(content),
+        }
+    }
+
+    fn get_import_kinds(&self) -> Vec<&'static str> {
+        vec![]
+    }
+
+    fn get_call_kinds(&self) -> Vec<&'static str> {
+        vec![]
+    }
+
+    fn get_doc_comment(&self, _code: &str, _node: &Node) -> Option<String> {
+        None
+    }
+}
+
+/**
+* 大纲节点（ATX 标题的 section 或 setext 标题）的标题级别，setext 标题按下划线分为一级和二级
+*/
+fn get_heading_level(node: &Node) -> Option<usize> {
+    match node.kind() {
+        "section" => {
+            let heading = node
+                .named_child(0)
+                .filter(|heading| heading.kind() == "atx_heading")?;
+            heading
+                .child(0)?
+                .kind()
+                .strip_prefix("atx_h")?
+                .strip_suffix("_marker")?
+                .parse()
+                .ok()
+        }
+        "setext_heading" => {
+            let underline = node.child(node.child_count().checked_sub(1)?)?;
+            match underline.kind() {
+                "setext_h1_underline" => Some(1),
+                _ => Some(2),
+            }
+        }
+        _ => None,
+    }
+}
+
+/**
+* 文档顺序中的上一个块，只进入 section 内部查找，没有前一个兄弟节点时为父节点
+*/
+fn get_prev_block<'a>(node: &Node<'a>) -> Option<Node<'a>> {
+    let Some(mut prev) = node.prev_named_sibling() else {
+        return node.parent();
+    };
+    while prev.kind() == "section" {
+        let Some(last) = prev
+            .named_child_count()
+            .checked_sub(1)
+            .and_then(|index| prev.named_child(index))
+        else {
+            break;
+        };
+        prev = last;
+    }
+    Some(prev)
+}
+
+/**
+* 文档顺序中跳过节点内部的下一个块
+*/
+fn get_next_block<'a>(node: &Node<'a>) -> Option<Node<'a>> {
+    let mut current = *node;
+    loop {
+        if let Some(next) = current.next_named_sibling() {
+            return Some(next);
+        }
+        current = current.parent()?;
+    }
+}
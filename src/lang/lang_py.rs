@@ -1,10 +1,13 @@
 use tree_sitter::{Language, Node};
 
-use super::{get_defination_string, PythonQuery, SymbolQuery};
+use super::{
+    get_definition_tokens, get_preceding_comments, get_value_preview, Definition, PythonQuery,
+    SymbolQuery, TokenKind,
+};
 
 impl SymbolQuery for PythonQuery {
     fn get_queries(&self) -> Vec<String> {
-        return vec![
+        vec![
             String::from(
                 r#"
             ((function_definition
@@ -19,7 +22,22 @@ impl SymbolQuery for PythonQuery {
                     (#match? @class ":?"))
                 "#,
             ),
-        ];
+            String::from(
+                r#"
+                ((class_definition
+                    body:(block (expression_statement (assignment left:(identifier) @field))))
+                    (#match? @field ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ((module
+                    (expression_statement (assignment left:(identifier) @constant)))
+                    (#match? @constant "^[A-Z][A-Z0-9_]*$")
+                    (#match? @constant ":?"))
+                "#,
+            ),
+        ]
     }
 
     fn get_lang(&self) -> Language {
@@ -28,19 +46,120 @@ impl SymbolQuery for PythonQuery {
 
     fn is_key_node(&self, node: &Node) -> bool {
         match node.kind() {
-            "class_definition" | "function_definition" => true,
+            "decorated_definition" => true,
+            // 带装饰器的定义由 decorated_definition 作为大纲节点
+            "class_definition" | "function_definition" => node
+                .parent()
+                .is_none_or(|parent| parent.kind() != "decorated_definition"),
+            "expression_statement" => get_class_attribute(node).is_some(),
             _ => false,
         }
     }
 
-    fn get_definition(&self, code: &String, node: &Node) -> String {
+    fn get_definition(&self, code: &str, node: &Node) -> Definition {
+        if node.kind() == "decorated_definition" {
+            let mut definition = Definition::default();
+            for decorator in node.children(&mut node.walk()) {
+                if decorator.kind() == "decorator" {
+                    definition.push(TokenKind::Text, &code[decorator.byte_range()]);
+                }
+            }
+            if let Some(inner) = node.child_by_field_name("definition") {
+                definition
+                    .tokens
+                    .extend(self.get_definition(code, &inner).tokens);
+            }
+            return definition;
+        }
+        if let Some(assignment) = get_class_attribute(node) {
+            return self.get_definition(code, &assignment);
+        }
+        if node.kind() == "assignment" {
+            let mut definition = Definition::default();
+            for child in node.children(&mut node.walk()) {
+                let text = &code[child.byte_range()];
+                if node.child_by_field_name("left") == Some(child) {
+                    definition.push(TokenKind::Name, text);
+                } else {
+                    definition.push(TokenKind::Text, &get_value_preview(code, &child));
+                }
+            }
+            return definition;
+        }
         let node_type = node.kind();
-        let definition_list = vec![
-            ("class_definition", "block"),
-            ("function_definition", "parameters"),
-        ];
-        let keywords = vec!["class", "def"];
+        let definition_list = vec![("class_definition", ":"), ("function_definition", ":")];
+        let keywords = vec!["class", "def", "async"];
+
+        get_definition_tokens(definition_list, keywords, code, node_type, node)
+    }
+
+    fn get_container<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            "class_definition" => node.child_by_field_name("name"),
+            _ => None,
+        }
+    }
+
+    fn is_type_container(&self, node: &Node) -> bool {
+        node.kind() == "class_definition"
+    }
+
+    fn get_import_kinds(&self) -> Vec<&'static str> {
+        vec!["import_statement", "import_from_statement"]
+    }
+
+    fn get_symbol_name<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            "decorated_definition" => node.child_by_field_name("definition")?,
+            "expression_statement" => {
+                return get_class_attribute(node)?.child_by_field_name("left")
+            }
+            _ => *node,
+        }
+        .child_by_field_name("name")
+    }
+
+    fn get_call_kinds(&self) -> Vec<&'static str> {
+        vec!["call"]
+    }
+
+    fn get_doc_comment(&self, code: &str, node: &Node) -> Option<String> {
+        // 优先使用函数体、类体中的第一个字符串作为 docstring
+        let definition = match node.kind() {
+            "decorated_definition" => node.child_by_field_name("definition"),
+            _ => Some(*node),
+        };
+        let docstring = definition
+            .and_then(|definition| definition.child_by_field_name("body"))
+            .and_then(|body| body.named_child(0))
+            .filter(|statement| statement.kind() == "expression_statement")
+            .and_then(|statement| statement.named_child(0))
+            .filter(|string| string.kind() == "string");
+        match docstring {
+            Some(string) => {
+                let text = code[string.byte_range()]
+                    .trim_start_matches(['r', 'u', 'b'])
+                    .trim_matches(['"', '\'']);
+                Some(text.lines().map(str::trim).collect::<Vec<_>>().join("\n"))
+                    .filter(|doc| !doc.trim().is_empty())
+                    .map(|doc| doc.trim().to_string())
+            }
+            None => get_preceding_comments(code, node),
+        }
+    }
+}
 
-        return get_defination_string(definition_list, keywords, code, node_type, node);
+/**
+ * 类体中的赋值语句，如 `sides = 0`，作为类的字段
+ */
+fn get_class_attribute<'a>(node: &Node<'a>) -> Option<Node<'a>> {
+    if node.kind() != "expression_statement" {
+        return None;
     }
+    let in_class = node
+        .parent()
+        .and_then(|block| block.parent())
+        .is_some_and(|parent| parent.kind() == "class_definition");
+    node.named_child(0)
+        .filter(|assignment| in_class && assignment.kind() == "assignment")
 }
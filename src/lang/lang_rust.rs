@@ -1,10 +1,10 @@
 use tree_sitter::{Language, Node};
 
-use super::{get_defination_string, RustQuery, SymbolQuery};
+use super::{get_definition_tokens, Definition, RustQuery, SymbolQuery};
 
 impl SymbolQuery for RustQuery {
     fn get_queries(&self) -> Vec<String> {
-        return vec![
+        vec![
             String::from(
                 r#"
             ((function_item
@@ -12,6 +12,13 @@ impl SymbolQuery for RustQuery {
              (#match? @function ":?"))
             "#,
             ),
+            String::from(
+                r#"
+            ((function_signature_item
+             name:(identifier) @function)
+             (#match? @function ":?"))
+            "#,
+            ),
             String::from(
                 r#"
                 ((struct_item
@@ -19,7 +26,64 @@ impl SymbolQuery for RustQuery {
                     (#match? @struct ":?"))
                 "#,
             ),
-        ];
+            String::from(
+                r#"
+                ((trait_item
+                    name:(type_identifier) @interface)
+                    (#match? @interface ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ((enum_item
+                    name:(type_identifier) @enum)
+                    (#match? @enum ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ([(type_item name:(type_identifier) @type)
+                  (union_item name:(type_identifier) @type)
+                  (associated_type name:(type_identifier) @type)]
+                    (#match? @type ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ((mod_item
+                    name:(identifier) @module)
+                    (#match? @module ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ((macro_definition
+                    name:(identifier) @macro)
+                    (#match? @macro ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ((field_declaration
+                    name:(field_identifier) @field)
+                    (#match? @field ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ((enum_variant
+                    name:(identifier) @variant)
+                    (#match? @variant ":?"))
+                "#,
+            ),
+            String::from(
+                r#"
+                ([(const_item name:(identifier) @constant)
+                  (static_item name:(identifier) @constant)]
+                    (#match? @constant ":?"))
+                "#,
+            ),
+        ]
     }
 
     fn get_lang(&self) -> Language {
@@ -27,27 +91,98 @@ impl SymbolQuery for RustQuery {
     }
 
     fn is_key_node(&self, node: &Node) -> bool {
-        match node.kind() {
+        matches!(
+            node.kind(),
             "function_item"
-            | "struct_item"
-            | "impl_item"
-            | "trait_item"
-            | "function_signature_item" => true,
-            _ => false,
-        }
+                | "function_signature_item"
+                | "struct_item"
+                | "enum_item"
+                | "union_item"
+                | "impl_item"
+                | "trait_item"
+                | "mod_item"
+                | "const_item"
+                | "static_item"
+                | "type_item"
+                | "associated_type"
+                | "macro_definition"
+                | "field_declaration"
+                | "enum_variant"
+        )
     }
 
-    fn get_definition(&self, code: &String, node: &Node) -> String {
+    fn get_definition(&self, code: &str, node: &Node) -> Definition {
         let node_type = node.kind();
         let definition_list = vec![
-            ("function_item", "parameters"),
+            ("function_item", "block"),
             ("impl_item", "declaration_list"),
             ("struct_item", "field_declaration_list"),
+            ("enum_item", "enum_variant_list"),
+            ("union_item", "field_declaration_list"),
             ("trait_item", "declaration_list"),
-            ("function_signature_item", "parameters"),
+            ("function_signature_item", ";"),
+            ("mod_item", "declaration_list"),
+            // 常量只展示类型，不展示值
+            ("const_item", "="),
+            ("static_item", "="),
+            ("type_item", ";"),
+            ("associated_type", ";"),
+            ("macro_definition", "{"),
+            ("field_declaration", ""),
+            ("enum_variant", ""),
         ];
-        let keywords = vec!["fn", "for", "impl", "where", "struct", "pub", "trait"];
+        let keywords = vec![
+            "fn",
+            "for",
+            "impl",
+            "where",
+            "struct",
+            "enum",
+            "union",
+            "pub",
+            "trait",
+            "mod",
+            "const",
+            "static",
+            "type",
+            "async",
+            "unsafe",
+            "macro_rules!",
+        ];
+
+        get_definition_tokens(definition_list, keywords, code, node_type, node)
+    }
+
+    fn get_container<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            "mod_item" | "trait_item" | "struct_item" | "enum_item" | "union_item" => {
+                node.child_by_field_name("name")
+            }
+            "impl_item" => node.child_by_field_name("type"),
+            _ => None,
+        }
+    }
+
+    fn is_type_container(&self, node: &Node) -> bool {
+        matches!(node.kind(), "impl_item" | "trait_item")
+    }
+
+    fn get_separator(&self) -> &'static str {
+        "::"
+    }
+
+    fn get_identifier_kinds(&self) -> Vec<&'static str> {
+        vec!["identifier", "type_identifier", "field_identifier"]
+    }
+
+    fn get_symbol_name<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            "impl_item" => node.child_by_field_name("type"),
+            _ => node.child_by_field_name("name"),
+        }
+    }
 
-        return get_defination_string(definition_list, keywords, code, node_type, node);
+    fn get_import_kinds(&self) -> Vec<&'static str> {
+        vec!["use_declaration", "extern_crate_declaration"]
     }
 }
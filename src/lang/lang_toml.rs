@@ -0,0 +1,104 @@
+use tree_sitter::{Language, Node};
+
+use super::{get_index_segment, get_value_preview, Definition, SymbolQuery, TokenKind, TomlQuery};
+
+impl SymbolQuery for TomlQuery {
+    fn get_queries(&self) -> Vec<String> {
+        ["table", "table_array_element", "pair"]
+            .iter()
+            .map(|kind| {
+                format!(
+                    r#"
+            (({kind}
+             . [(bare_key) (dotted_key) (quoted_key)] @key)
+             (#match? @key ":?"))
+            "#
+                )
+            })
+            .collect()
+    }
+
+    fn get_lang(&self) -> Language {
+        tree_sitter_toml_ng::LANGUAGE.into()
+    }
+
+    fn is_key_node(&self, node: &Node) -> bool {
+        matches!(node.kind(), "table" | "table_array_element" | "pair")
+    }
+
+    fn get_definition(&self, code: &str, node: &Node) -> Definition {
+        let mut definition = Definition::default();
+        let Some(name) = self.get_symbol_name(node) else {
+            return definition;
+        };
+        let key = &code[name.byte_range()];
+        match node.kind() {
+            "table" => definition.push(TokenKind::Name, &format!("[{key}]")),
+            "table_array_element" => definition.push(TokenKind::Name, &format!("[[{key}]]")),
+            _ => {
+                definition.push(TokenKind::Name, key);
+                // 内联表的键作为子节点展示
+                if let Some(value) = name.next_named_sibling() {
+                    if value.kind() != "inline_table" {
+                        definition.push(TokenKind::Text, "=");
+                        definition.push(TokenKind::Text, &get_value_preview(code, &value));
+                    }
+                }
+            }
+        }
+        definition
+    }
+
+    fn get_container<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        if self.is_key_node(node) {
+            self.get_symbol_name(node)
+        } else {
+            None
+        }
+    }
+
+    fn get_path_segment(&self, code: &str, node: &Node) -> Option<String> {
+        match node.kind() {
+            // 同名的 [[bin]] 按出现顺序编号，如 `bin[1]`
+            "table_array_element" => {
+                let key = &code[self.get_symbol_name(node)?.byte_range()];
+                let mut index = 0;
+                let mut sibling = node.prev_named_sibling();
+                while let Some(current) = sibling {
+                    if current.kind() == "table_array_element"
+                        && self
+                            .get_symbol_name(&current)
+                            .is_some_and(|name| &code[name.byte_range()] == key)
+                    {
+                        index += 1;
+                    }
+                    sibling = current.prev_named_sibling();
+                }
+                Some(format!("{key}[{index}]"))
+            }
+            "inline_table" if node.parent().is_some_and(|parent| parent.kind() == "array") => {
+                get_index_segment(node)
+            }
+            _ => self
+                .get_container(node)
+                .map(|name| code[name.byte_range()].to_string()),
+        }
+    }
+
+    fn get_identifier_kinds(&self) -> Vec<&'static str> {
+        vec![]
+    }
+
+    fn get_symbol_name<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        node.named_child(0)
+            .filter(|key| matches!(key.kind(), "bare_key" | "dotted_key" | "quoted_key"))
+    }
+
+    fn get_import_kinds(&self) -> Vec<&'static str> {
+        vec![]
+    }
+
+    fn get_call_kinds(&self) -> Vec<&'static str> {
+        vec![]
+    }
+}
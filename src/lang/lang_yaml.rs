@@ -0,0 +1,102 @@
+use tree_sitter::{Language, Node};
+
+use super::{get_index_segment, get_value_preview, Definition, SymbolQuery, TokenKind, YamlQuery};
+
+impl SymbolQuery for YamlQuery {
+    fn get_queries(&self) -> Vec<String> {
+        vec![
+            String::from(
+                r#"
+            ((block_mapping_pair
+             key:(flow_node [(plain_scalar) (double_quote_scalar) (single_quote_scalar)] @key))
+             (#match? @key ":?"))
+            "#,
+            ),
+            String::from(
+                r#"
+            ((flow_pair
+             key:(flow_node [(plain_scalar) (double_quote_scalar) (single_quote_scalar)] @key))
+             (#match? @key ":?"))
+            "#,
+            ),
+        ]
+    }
+
+    fn get_lang(&self) -> Language {
+        tree_sitter_yaml::LANGUAGE.into()
+    }
+
+    fn is_key_node(&self, node: &Node) -> bool {
+        match node.kind() {
+            "block_mapping_pair" | "flow_pair" => true,
+            // 列表中的映射作为大纲节点，以下标区分
+            "block_sequence_item" => node
+                .named_child(0)
+                .is_some_and(|value| value.kind() == "block_node"),
+            _ => false,
+        }
+    }
+
+    fn get_definition(&self, code: &str, node: &Node) -> Definition {
+        let mut definition = Definition::default();
+        if node.kind() == "block_sequence_item" {
+            definition.push(
+                TokenKind::Name,
+                &get_index_segment(node).unwrap_or_default(),
+            );
+            return definition;
+        }
+        let Some(name) = self.get_symbol_name(node) else {
+            return definition;
+        };
+        let key = &code[name.byte_range()];
+        let scalar = node
+            .child_by_field_name("value")
+            .filter(|value| value.kind() == "flow_node")
+            .and_then(|value| value.named_child(0))
+            .filter(|value| value.kind().ends_with("scalar"));
+        match scalar {
+            Some(value) => {
+                definition.push(TokenKind::Name, &format!("{key}:"));
+                definition.push(TokenKind::Text, &get_value_preview(code, &value));
+            }
+            None => definition.push(TokenKind::Name, key),
+        }
+        definition
+    }
+
+    fn get_container<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match node.kind() {
+            "block_mapping_pair" | "flow_pair" => self.get_symbol_name(node),
+            _ => None,
+        }
+    }
+
+    fn get_path_segment(&self, code: &str, node: &Node) -> Option<String> {
+        let in_sequence = node.kind() == "block_sequence_item"
+            || node
+                .parent()
+                .is_some_and(|parent| parent.kind() == "flow_sequence");
+        if in_sequence {
+            return get_index_segment(node);
+        }
+        self.get_container(node)
+            .map(|name| code[name.byte_range()].to_string())
+    }
+
+    fn get_identifier_kinds(&self) -> Vec<&'static str> {
+        vec![]
+    }
+
+    fn get_symbol_name<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        node.child_by_field_name("key")?.named_child(0)
+    }
+
+    fn get_import_kinds(&self) -> Vec<&'static str> {
+        vec![]
+    }
+
+    fn get_call_kinds(&self) -> Vec<&'static str> {
+        vec![]
+    }
+}
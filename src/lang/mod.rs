@@ -1,4 +1,4 @@
-use colored::Colorize;
+use std::{fmt, str::FromStr};
 use tree_sitter::{Language, Node};
 mod lang_c;
 mod lang_cpp;
@@ -6,63 +6,481 @@ mod lang_csharp;
 mod lang_go;
 mod lang_java;
 mod lang_js;
+mod lang_json;
+mod lang_md;
 mod lang_py;
 mod lang_rust;
+mod lang_toml;
+mod lang_yaml;
 
-pub trait SymbolQuery {
+pub trait SymbolQuery: fmt::Debug {
     fn get_queries(&self) -> Vec<String>;
     fn get_lang(&self) -> Language;
     fn is_key_node(&self, node: &Node) -> bool;
-    fn get_definition(&self, code: &String, node: &Node) -> String;
+    fn get_definition(&self, code: &str, node: &Node) -> Definition;
+    /**
+     * 如果节点是容器（模块、命名空间、类、impl 等），返回容器名称所在的节点
+     */
+    fn get_container<'a>(&self, _node: &Node<'a>) -> Option<Node<'a>> {
+        None
+    }
+    /**
+     * 节点是否为类型容器（类、结构体、impl 等），其中直接定义的函数作为方法
+     */
+    fn is_type_container(&self, _node: &Node) -> bool {
+        false
+    }
+    /**
+     * 容器在限定名称中的片段，默认为容器名称
+     *
+     * 以 `[` 开头的片段（如数组下标 `[0]`）直接拼接在上一级之后
+     */
+    fn get_path_segment(&self, code: &str, node: &Node) -> Option<String> {
+        self.get_container(node)
+            .map(|name| code[name.byte_range()].to_string())
+    }
+    /**
+     * 限定名称中各级容器之间的分隔符
+     */
+    fn get_separator(&self) -> &'static str {
+        "."
+    }
+    /**
+     * 限定名称和大纲层级中的上一级节点，默认为语法树中的父节点
+     *
+     * 层级不由语法树嵌套决定的语言（如按级别嵌套的 Markdown 标题）需要重写
+     */
+    fn get_parent<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        node.parent()
+    }
+    /**
+     * 大纲节点的结束行（从 1 开始）
+     */
+    fn get_end_line(&self, node: &Node) -> usize {
+        get_node_end_line(node)
+    }
+    /**
+     * 标识符节点的类型，用于查找引用
+     */
+    fn get_identifier_kinds(&self) -> Vec<&'static str> {
+        vec!["identifier"]
+    }
+    /**
+     * 获取大纲节点（见 `is_key_node`）的名称节点
+     */
+    fn get_symbol_name<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        node.child_by_field_name("name")
+    }
+    /**
+     * 导入语句的节点类型，如 `use_declaration`、`import_statement`
+     */
+    fn get_import_kinds(&self) -> Vec<&'static str>;
+    /**
+     * 函数调用的节点类型
+     */
+    fn get_call_kinds(&self) -> Vec<&'static str> {
+        vec!["call_expression"]
+    }
+    /**
+     * 获取调用表达式中被调用函数的名称节点，如 `a.b()` 中的 `b`
+     *
+     * 下标等没有名称的调用（如 `handlers[key]()`）返回 None
+     */
+    fn get_callee<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        let identifier_kinds = self.get_identifier_kinds();
+        let mut callee = node.child_by_field_name("function")?;
+        while !identifier_kinds.contains(&callee.kind()) {
+            // 嵌套调用 `f(x)()` 和泛型调用 `f::<T>()` 取 function 字段，成员访问和路径取最后的名称
+            callee = ["function", "field", "attribute", "name"]
+                .into_iter()
+                .find_map(|field| callee.child_by_field_name(field))?;
+        }
+        Some(callee)
+    }
+    /**
+     * 获取定义节点的文档注释，默认取紧邻其前的注释
+     */
+    fn get_doc_comment(&self, code: &str, node: &Node) -> Option<String> {
+        get_preceding_comments(code, node)
+    }
+}
+
+/**
+* 符号类型，由查询中的捕获名称（如 `@function`、`@class`）归一化而来
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Struct,
+    Class,
+    Interface,
+    Type,
+    Enum,
+    /// 模块、命名空间
+    Module,
+    Macro,
+    /// 字段、属性
+    Field,
+    /// 枚举成员
+    Variant,
+    Constant,
+    /// Markdown 标题
+    Heading,
+    /// 配置文件中的键
+    Key,
+}
+
+impl SymbolKind {
+    /**
+     * 根据查询捕获名称获取符号类型
+     */
+    pub fn from_capture(capture_name: &str) -> Option<SymbolKind> {
+        match capture_name {
+            "function" => Some(SymbolKind::Function),
+            "method" => Some(SymbolKind::Method),
+            "struct" => Some(SymbolKind::Struct),
+            "class" => Some(SymbolKind::Class),
+            "interface" => Some(SymbolKind::Interface),
+            "type" => Some(SymbolKind::Type),
+            "enum" => Some(SymbolKind::Enum),
+            "module" => Some(SymbolKind::Module),
+            "macro" => Some(SymbolKind::Macro),
+            "field" => Some(SymbolKind::Field),
+            "variant" => Some(SymbolKind::Variant),
+            "constant" => Some(SymbolKind::Constant),
+            "heading" => Some(SymbolKind::Heading),
+            "key" => Some(SymbolKind::Key),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SymbolKind::Function => "fn",
+            SymbolKind::Method => "method",
+            SymbolKind::Struct => "struct",
+            SymbolKind::Class => "class",
+            SymbolKind::Interface => "interface",
+            SymbolKind::Type => "type",
+            SymbolKind::Enum => "enum",
+            SymbolKind::Module => "mod",
+            SymbolKind::Macro => "macro",
+            SymbolKind::Field => "field",
+            SymbolKind::Variant => "variant",
+            SymbolKind::Constant => "const",
+            SymbolKind::Heading => "heading",
+            SymbolKind::Key => "key",
+        }
+    }
+}
+
+impl fmt::Display for SymbolKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SymbolKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "fn" | "func" | "function" => Ok(SymbolKind::Function),
+            "method" => Ok(SymbolKind::Method),
+            "struct" => Ok(SymbolKind::Struct),
+            "class" => Ok(SymbolKind::Class),
+            "interface" | "trait" => Ok(SymbolKind::Interface),
+            "type" => Ok(SymbolKind::Type),
+            "enum" => Ok(SymbolKind::Enum),
+            "mod" | "module" | "namespace" | "package" => Ok(SymbolKind::Module),
+            "macro" => Ok(SymbolKind::Macro),
+            "field" | "property" => Ok(SymbolKind::Field),
+            "variant" | "enum_member" => Ok(SymbolKind::Variant),
+            "const" | "constant" | "static" => Ok(SymbolKind::Constant),
+            "heading" => Ok(SymbolKind::Heading),
+            "key" => Ok(SymbolKind::Key),
+            other => Err(format!("未知的符号类型: {other}")),
+        }
+    }
+}
+
+/**
+* 解析逗号分隔的符号类型列表，如 `fn,class`
+*/
+pub fn parse_symbol_kinds(kinds: &str) -> Result<Vec<SymbolKind>, String> {
+    kinds
+        .split(',')
+        .filter(|kind| !kind.trim().is_empty())
+        .map(SymbolKind::from_str)
+        .collect()
 }
 
+#[derive(Debug)]
 pub struct RustQuery;
 
+#[derive(Debug)]
 pub struct JavaQuery;
 
+#[derive(Debug)]
 pub struct PythonQuery;
 
+#[derive(Debug)]
 pub struct JavascriptQuery;
 
+#[derive(Debug)]
 pub struct CQuery;
 
+#[derive(Debug)]
 pub struct CppQuery;
 
+#[derive(Debug)]
 pub struct GoQuery;
 
+#[derive(Debug)]
 pub struct CSharpQuery;
 
+#[derive(Debug)]
+pub struct MarkdownQuery;
+
+#[derive(Debug)]
+pub struct JsonQuery;
+
+#[derive(Debug)]
+pub struct YamlQuery;
+
+#[derive(Debug)]
+pub struct TomlQuery;
+
 /**
-* 获取类型定义的字符串
+* 节点的结束行，以换行结尾的节点（如 Markdown 的 section）不计入下一行
 */
-pub fn get_defination_string(
+pub fn get_node_end_line(node: &Node) -> usize {
+    let end = node.end_position();
+    if end.column == 0 && end.row > node.start_position().row {
+        end.row
+    } else {
+        end.row + 1
+    }
+}
+
+/**
+* 沿 declarator 字段向下查找 C/C++ 声明的名称节点
+*/
+pub fn get_declarator_name<'a>(node: &Node<'a>) -> Option<Node<'a>> {
+    let mut declarator = node.child_by_field_name("declarator")?;
+    while let Some(inner) = declarator.child_by_field_name("declarator") {
+        declarator = inner;
+    }
+    Some(declarator)
+}
+
+/**
+* 获取紧邻节点之前的连续注释，跳过中间的属性（如 `#[derive(...)]`）
+*/
+pub fn get_preceding_comments(code: &str, node: &Node) -> Option<String> {
+    let mut comments = vec![];
+    let mut row = node.start_position().row;
+    let mut current = *node;
+    while let Some(prev) = current.prev_sibling() {
+        // 注释与下一个节点之间不能有空行
+        if prev.end_position().row + 1 < row {
+            break;
+        }
+        if prev.kind().contains("comment") {
+            comments.push(clean_comment(&code[prev.byte_range()]));
+        } else if prev.kind() != "attribute_item" {
+            break;
+        }
+        row = prev.start_position().row;
+        current = prev;
+    }
+    comments.reverse();
+    let doc = comments.join("\n").trim().to_string();
+    if doc.is_empty() {
+        None
+    } else {
+        Some(doc)
+    }
+}
+
+/**
+* 去掉行首、行尾的注释符号，如 `///`、`*`、`#`
+*/
+pub fn clean_comment(comment: &str) -> String {
+    let mut lines = vec![];
+    for line in comment.lines() {
+        let mut line = line.trim();
+        line = line.strip_suffix("*/").unwrap_or(line);
+        for prefix in ["///", "//!", "//", "/**", "/*", "*", "#"] {
+            if let Some(stripped) = line.strip_prefix(prefix) {
+                line = stripped;
+                break;
+            }
+        }
+        lines.push(line.trim());
+    }
+    lines.join("\n").trim().to_string()
+}
+
+/**
+* 配置项的值，过长时截断
+*/
+pub fn get_value_preview(code: &str, node: &Node) -> String {
+    let value = code[node.byte_range()]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if value.chars().count() > 40 {
+        format!("{}…", value.chars().take(40).collect::<String>())
+    } else {
+        value
+    }
+}
+
+/**
+* 数组元素的下标片段，如 `[0]`，只统计与节点同类型的兄弟节点
+*/
+pub fn get_index_segment(node: &Node) -> Option<String> {
+    let parent = node.parent()?;
+    let index = parent
+        .named_children(&mut parent.walk())
+        .filter(|sibling| sibling.kind() == node.kind())
+        .position(|sibling| sibling == *node)?;
+    Some(format!("[{index}]"))
+}
+
+/**
+* 定义中片段的类型
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Keyword,
+    Name,
+    Params,
+    ReturnType,
+    Text,
+}
+
+/**
+* 定义中的一个片段
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DefinitionToken {
+    pub kind: TokenKind,
+    pub text: String,
+}
+
+/**
+* 结构化的定义（签名），不包含颜色，由渲染器决定如何展示
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Definition {
+    pub tokens: Vec<DefinitionToken>,
+}
+
+impl Definition {
+    pub fn push(&mut self, kind: TokenKind, text: &str) {
+        if !text.is_empty() {
+            self.tokens.push(DefinitionToken {
+                kind,
+                text: text.to_string(),
+            });
+        }
+    }
+
+    /**
+     * 按空格拆分文本，关键字单独作为一个片段
+     */
+    pub fn push_words(&mut self, text: &str, keywords: &[&str]) {
+        for word in text.split(' ') {
+            if keywords.contains(&word) {
+                self.push(TokenKind::Keyword, word);
+            } else {
+                self.push(TokenKind::Text, word);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+}
+
+impl fmt::Display for Definition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let texts: Vec<&str> = self
+            .tokens
+            .iter()
+            .map(|token| token.text.as_str())
+            .collect();
+        f.write_str(&texts.join(" "))
+    }
+}
+
+/**
+* 获取类型定义的片段
+*
+* definition_list 中为 (定义节点类型, 结束节点类型)，遇到结束节点或 body 字段（通常是函数体、类体）时停止
+*/
+pub fn get_definition_tokens(
     definition_list: Vec<(&str, &str)>,
     keywords: Vec<&str>,
-    code: &String,
+    code: &str,
     node_type: &str,
     node: &Node,
-) -> String {
-    let mut output = String::new();
+) -> Definition {
+    let mut definition = Definition::default();
     for (root_type, end_type) in definition_list {
         if node_type == root_type {
+            let body = node.child_by_field_name("body");
             for child in node.children(&mut node.walk()) {
-                if child.kind() == end_type {
+                if child.kind() == end_type || Some(child) == body || child.kind() == ";" {
                     break;
-                } else {
-                    let node_text = &code[child.byte_range()];
-                    // println!("node_text:{}, node_kind: {}", node_text, child.kind());
-                    for node_text_item in node_text.split(" ").enumerate() {
-                        if keywords.contains(&node_text_item.1) {
-                            output.push_str(node_text_item.1.purple().to_string().as_str());
-                        } else {
-                            output.push_str(node_text_item.1);
-                        }
-                        output.push(' ');
-                    }
                 }
+                push_child_tokens(&mut definition, &keywords, code, node, &child);
             }
             break;
         }
     }
-    return output;
+    definition
+}
+
+/**
+* 根据子节点在定义节点中的字段，区分名称、参数和返回类型
+*/
+fn push_child_tokens(
+    definition: &mut Definition,
+    keywords: &[&str],
+    code: &str,
+    node: &Node,
+    child: &Node,
+) {
+    let is_field = |field: &str| node.child_by_field_name(field) == Some(*child);
+    let has_params = node.child_by_field_name("parameters").is_some();
+    let text = &code[child.byte_range()];
+    if child.kind() == "function_declarator" {
+        // C/C++ 的名称和参数在 declarator 中
+        for sub_child in child.children(&mut child.walk()) {
+            push_child_tokens(definition, keywords, code, child, &sub_child);
+        }
+    } else if is_field("name") || (node.kind() == "function_declarator" && is_field("declarator")) {
+        definition.push(TokenKind::Name, text);
+    } else if is_field("parameters") {
+        definition.push(TokenKind::Params, text);
+    } else if (has_params
+        || node
+            .child_by_field_name("declarator")
+            .is_some_and(|declarator| declarator.kind() == "function_declarator"))
+        && ["return_type", "result", "returns", "type"]
+            .iter()
+            .any(|field| is_field(field))
+    {
+        definition.push(TokenKind::ReturnType, text);
+    } else if let Some(body) = child.child_by_field_name("body") {
+        // 内联的结构体等只保留定义体之前的部分，如 `typedef struct {...} Person`
+        definition.push_words(code[child.start_byte()..body.start_byte()].trim(), keywords);
+    } else {
+        definition.push_words(text, keywords);
+    }
 }
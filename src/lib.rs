@@ -1,25 +1,51 @@
+use aho_corasick::{AhoCorasick, MatchKind};
+pub use callgraph::{find_calls, CallDirection, CallEdge, CallGraph};
 use colored::Colorize;
+pub use definition::{find_definitions, get_identifier_at, get_imports, parse_location, Location};
+pub use diagnostics::{find_parse_errors, print_parse_errors, ParseError};
+pub use editor::{get_editor, get_editor_args, open_in_editor};
+use encoding::needs_transcoding;
+pub use encoding::{decode_source, read_source, SourceEncoding};
+pub use fuzzy::{fuzzy_match, FuzzyMatch};
 use indicatif::{ProgressBar, ProgressFinish, ProgressStyle};
+pub use lang::{parse_symbol_kinds, Definition, DefinitionToken, SymbolKind, TokenKind};
 use lang::{
-    CQuery, CSharpQuery, CppQuery, GoQuery, JavaQuery, JavascriptQuery, PythonQuery, RustQuery,
-    SymbolQuery,
+    CQuery, CSharpQuery, CppQuery, GoQuery, JavaQuery, JavascriptQuery, JsonQuery, MarkdownQuery,
+    PythonQuery, RustQuery, SymbolQuery, TomlQuery, YamlQuery,
 };
+use memmap2::Mmap;
+pub use query::{IndexQuery, RankedIndex, Term};
+pub use refs::{find_references, print_references, Reference};
 use regex::Regex;
 use rustyline::{
     hint::{Hint, Hinter},
     Completer, Context, Helper, Highlighter, Validator,
 };
 use std::{
-    collections::HashSet,
+    cell::RefCell,
+    collections::{hash_map::Entry, HashMap, HashSet},
     ffi::OsStr,
     fs::{self, read_dir, File},
-    io::{BufRead, BufReader},
+    io::{ErrorKind, Read},
+    iter,
+    ops::{Deref, Range},
     path::{Path, PathBuf},
     rc::Rc,
+    str::FromStr,
 };
-use tree_sitter::{Node, Parser, Query, QueryCursor};
+use tree_sitter::{Language, Node, Parser, Query, QueryCursor, Tree};
+pub use tui::run_tui;
 
+mod callgraph;
+mod definition;
+mod diagnostics;
+mod editor;
+mod encoding;
+mod fuzzy;
 mod lang;
+mod query;
+mod refs;
+mod tui;
 
 #[derive(Completer, Helper, Highlighter, Validator)]
 pub struct CodeHinter {
@@ -97,6 +123,42 @@ pub fn diy_hints() -> HashSet<CommandHint> {
         format!("search {}", "path search_key".bright_black()).as_str(),
         "search ",
     ));
+    set.insert(CommandHint::new(
+        format!("refs {}", "符号名称".bright_black()).as_str(),
+        "refs ",
+    ));
+    set.insert(CommandHint::new(
+        format!("callers {}", "函数名称".bright_black()).as_str(),
+        "callers ",
+    ));
+    set.insert(CommandHint::new(
+        format!("callees {}", "函数名称".bright_black()).as_str(),
+        "callees ",
+    ));
+    set.insert(CommandHint::new(
+        format!("doc:{}", "文档关键字".bright_black()).as_str(),
+        "doc:",
+    ));
+    set.insert(CommandHint::new(
+        format!("kind:{}", "fn,class".bright_black()).as_str(),
+        "kind:",
+    ));
+    set.insert(CommandHint::new(
+        format!("lang:{}", "扩展名".bright_black()).as_str(),
+        "lang:",
+    ));
+    set.insert(CommandHint::new(
+        format!("path:{}", "路径".bright_black()).as_str(),
+        "path:",
+    ));
+    set.insert(CommandHint::new(
+        format!("name:{}", "名称通配符".bright_black()).as_str(),
+        "name:",
+    ));
+    set.insert(CommandHint::new(
+        format!("open {}", "结果序号".bright_black()).as_str(),
+        "open ",
+    ));
     set.insert(CommandHint::new("quit()", "quit()"));
     set
 }
@@ -132,11 +194,12 @@ fn valid_language_file(extention: &str) -> bool {
         "h",
         "hpp",
         "toml",
+        "json",
         "yaml",
         "yml",
         "properties",
     ];
-    return valid_extensions.contains(&extention);
+    valid_extensions.contains(&extention)
 }
 /*
 * 递归目录
@@ -149,16 +212,14 @@ pub fn recursion_dir(root_path: &Path, pathes: &mut Vec<PathBuf>, filter: &str)
 
             recursion_dir(path_buf.as_path(), pathes, filter);
         }
-    } else if root_path.is_file() {
-        if root_path.extension().is_some() {
-            let extension = root_path
-                .extension()
-                .unwrap_or(OsStr::new(""))
-                .to_str()
-                .unwrap();
-            if (filter.is_empty() || filter == extension) && valid_language_file(extension) {
-                pathes.push(root_path.to_path_buf());
-            }
+    } else if root_path.is_file() && root_path.extension().is_some() {
+        let extension = root_path
+            .extension()
+            .unwrap_or(OsStr::new(""))
+            .to_str()
+            .unwrap();
+        if (filter.is_empty() || filter == extension) && valid_language_file(extension) {
+            pathes.push(root_path.to_path_buf());
         }
     }
 }
@@ -168,109 +229,853 @@ pub fn get_symbol_query(extention: &str) -> Box<dyn SymbolQuery> {
         "rs" => Box::new(RustQuery),
         "java" => Box::new(JavaQuery),
         "py" => Box::new(PythonQuery),
-        "c" => Box::new(CQuery),
+        "c" | "h" => Box::new(CQuery),
         "cs" => Box::new(CSharpQuery),
-        "cpp" => Box::new(CppQuery),
+        "cpp" | "hpp" => Box::new(CppQuery),
         "js" => Box::new(JavascriptQuery),
         "go" => Box::new(GoQuery),
+        "md" => Box::new(MarkdownQuery),
+        "json" => Box::new(JsonQuery),
+        "yaml" | "yml" => Box::new(YamlQuery),
+        "toml" => Box::new(TomlQuery),
         _ => Box::new(RustQuery),
     }
 }
+
+/**
+* 文件所属的语言，按扩展名区分，C 与 C++、yaml 与 yml 视为同一种语言
+*/
+pub fn get_language(path: &str) -> &str {
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("");
+    match extension {
+        "c" | "h" | "cpp" | "hpp" => "c",
+        "yml" => "yaml",
+        _ => extension,
+    }
+}
+
+/**
+* 是否支持符号搜索
+*/
+pub fn support_symbol(extention: &str) -> bool {
+    matches!(
+        extention,
+        "rs" | "java"
+            | "py"
+            | "c"
+            | "h"
+            | "cs"
+            | "cpp"
+            | "hpp"
+            | "js"
+            | "go"
+            | "md"
+            | "json"
+            | "yaml"
+            | "yml"
+            | "toml"
+    )
+}
+
+/**
+* 解析源码，得到语法树
+*
+* 语法错误不会导致解析失败，错误处生成 ERROR/MISSING 节点，其余部分照常索引；
+* 只有语法版本不兼容等情况才返回 None
+*/
+pub(crate) fn parse_code(code: &str, symbol_query: &dyn SymbolQuery) -> Option<Tree> {
+    PARSERS.with(|parsers| {
+        let mut parsers = parsers.borrow_mut();
+        let parser = match parsers.entry(symbol_query.get_lang()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let mut parser = Parser::new();
+                parser.set_language(entry.key()).ok()?;
+                entry.insert(parser)
+            }
+        };
+        parser.parse(code, None)
+    })
+}
+
+thread_local! {
+    /// 每种语言复用同一个解析器
+    static PARSERS: RefCell<HashMap<Language, Parser>> = RefCell::new(HashMap::new());
+    /// 每种语言编译好的符号查询
+    static SYMBOL_QUERIES: RefCell<HashMap<Language, Rc<Vec<Query>>>> =
+        RefCell::new(HashMap::new());
+}
+
+/**
+* 获取编译好的符号查询，每种语言只编译一次
+*
+* 查询中关键字占位的 `(#match? @capture ":?")` 谓词会被去掉，关键字在匹配时再过滤
+*/
+pub(crate) fn get_compiled_queries(symbol_query: &dyn SymbolQuery) -> Rc<Vec<Query>> {
+    let language = symbol_query.get_lang();
+    SYMBOL_QUERIES.with(|queries| {
+        queries
+            .borrow_mut()
+            .entry(language.clone())
+            .or_insert_with(|| {
+                let placeholder = Regex::new(r#"\(#match\?\s+@\w+\s+":\?"\)"#).unwrap();
+                // 查询是内置的，编译失败说明查询本身有误，直接报错而不是静默丢弃
+                let compiled = symbol_query
+                    .get_queries()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, text)| {
+                        Query::new(&language, &placeholder.replace_all(text, "")).unwrap_or_else(
+                            |err| panic!("invalid symbol query {index} of {symbol_query:?}: {err}"),
+                        )
+                    })
+                    .collect();
+                Rc::new(compiled)
+            })
+            .clone()
+    })
+}
+/**
+* 符号匹配方式
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymbolMatch {
+    /// 符号名称或限定名称包含关键字
+    #[default]
+    Literal,
+    /// 符号名称或限定名称以关键字开头
+    Prefix,
+    /// 关键字作为正则表达式
+    Regex,
+}
+
+impl SymbolMatch {
+    /**
+     * 将关键字转换为 `#match?` 谓词使用的正则表达式
+     */
+    pub fn to_pattern(self, search_key: &str) -> String {
+        match self {
+            SymbolMatch::Literal => regex::escape(search_key),
+            SymbolMatch::Prefix => format!("^{}", regex::escape(search_key)),
+            SymbolMatch::Regex => search_key.to_string(),
+        }
+    }
+}
+
+/**
+* 源码中的符号
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub line: usize,
+    pub name: String,
+    /// 包含容器路径的限定名称，如 `CodeHinter::hint`
+    pub qualified_name: String,
+    pub kind: SymbolKind,
+    /// 定义的签名，即 `get_definition` 的纯文本
+    pub signature: String,
+    /// 文档注释或 docstring
+    pub doc: Option<String>,
+}
+
+/**
+* 获取名称节点所属的定义节点（大纲节点），最多向上查找三层
+*
+* 字段名称可能嵌套在声明器中，如 C# 的 `field_declaration > variable_declaration > variable_declarator`
+*/
+fn get_definition_node<'a>(node: &Node<'a>, symbol_query: &dyn SymbolQuery) -> Option<Node<'a>> {
+    let parent = node.parent()?;
+    let mut current = Some(parent);
+    for _ in 0..3 {
+        let ancestor = current?;
+        if symbol_query.is_key_node(&ancestor) {
+            return Some(ancestor);
+        }
+        current = ancestor.parent();
+    }
+    Some(parent)
+}
+
+/**
+* 类型容器中定义的函数作为方法，模块中或嵌套在函数中的函数仍为函数
+*/
+fn get_symbol_kind(
+    kind: SymbolKind,
+    definition_node: &Node,
+    symbol_query: &dyn SymbolQuery,
+) -> SymbolKind {
+    if kind != SymbolKind::Function {
+        return kind;
+    }
+    let mut parent = definition_node.parent();
+    while let Some(current) = parent {
+        if symbol_query.is_type_container(&current) {
+            return SymbolKind::Method;
+        }
+        if symbol_query.is_key_node(&current) {
+            break;
+        }
+        parent = current.parent();
+    }
+    kind
+}
+
+/**
+* 合并空白，得到单行文本
+*/
+fn to_single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/**
+* 颜色输出方式
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// 输出到终端且未设置 `NO_COLOR` 时使用颜色
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /**
+     * 设置全局的颜色输出方式
+     */
+    pub fn apply(self) {
+        match self {
+            ColorChoice::Auto => colored::control::unset_override(),
+            ColorChoice::Always => colored::control::set_override(true),
+            ColorChoice::Never => colored::control::set_override(false),
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            other => Err(format!("未知的颜色选项: {other}")),
+        }
+    }
+}
+
+/**
+* 渲染定义，关键字高亮显示
+*/
+pub fn render_definition(definition: &Definition) -> String {
+    let texts: Vec<String> = definition
+        .tokens
+        .iter()
+        .map(|token| match token.kind {
+            TokenKind::Keyword => token.text.purple().to_string(),
+            _ => to_single_line(&token.text),
+        })
+        .collect();
+    texts.join(" ")
+}
+
+/**
+* 沿父节点向上查找容器，拼接符号的限定名称
+*/
+pub(crate) fn get_qualified_name(
+    code: &str,
+    node: &Node,
+    symbol_query: &dyn SymbolQuery,
+) -> String {
+    let mut path = vec![code[node.byte_range()].to_string()];
+    let mut parent = node.parent();
+    while let Some(current) = parent {
+        let segment = symbol_query.get_path_segment(code, &current);
+        // 定义节点本身（如类声明）不作为自己的容器，其片段替换名称，如 `[[bin]]` 的 `bin[0]`
+        if symbol_query.get_container(&current) == Some(*node) {
+            if let Some(segment) = segment {
+                path[0] = segment;
+            }
+        } else if let Some(segment) = segment {
+            path.push(segment);
+        }
+        parent = symbol_query.get_parent(&current);
+    }
+    path.reverse();
+    let mut qualified_name = String::new();
+    for segment in path {
+        // 数组下标直接拼接在上一级名称之后，如 `containers[0]`
+        if !qualified_name.is_empty() && !segment.starts_with('[') {
+            qualified_name.push_str(symbol_query.get_separator());
+        }
+        qualified_name.push_str(&segment);
+    }
+    qualified_name
+}
+
 /**
 * 获取源码中的所有符号
 *
 *
 */
 pub fn get_all_symbols(
-    code: &String,
+    code: &str,
     search_key: &str,
+    symbol_match: SymbolMatch,
     symbol_query: Box<dyn SymbolQuery>,
-) -> Vec<(usize, String)> {
-    let mut parser = Parser::new();
-    parser
-        .set_language(&symbol_query.get_lang())
-        .expect("Error load Rust grammer");
-    let tree = parser.parse(code.as_str(), None).unwrap();
+) -> Vec<Symbol> {
+    let Some(tree) = parse_code(code, symbol_query.as_ref()) else {
+        return vec![];
+    };
 
+    let pattern = symbol_match.to_pattern(search_key);
+    let Ok(matcher) = Regex::new(&pattern) else {
+        // 非法的正则表达式不应导致程序崩溃
+        return vec![];
+    };
+    // 限定名称的关键字无法只靠名称节点过滤，需要在得到限定名称后再匹配
+    let qualified_key = search_key.contains('.') || search_key.contains(':');
     let mut query_cursor = QueryCursor::new();
     let mut filed_vec = vec![];
-    for sq in symbol_query.get_queries() {
-        let query = Query::new(
-            &symbol_query.get_lang(),
-            sq.replace(":?", search_key).as_str(),
-        )
-        .unwrap();
-        let captures = query_cursor.captures(&query, tree.root_node(), code.as_bytes());
+    for query in get_compiled_queries(symbol_query.as_ref()).iter() {
+        let capture_names = query.capture_names();
+        let captures = query_cursor.captures(query, tree.root_node(), code.as_bytes());
         for (m, capture_index) in captures {
             let capture = m.captures[capture_index];
+            let Some(kind) = SymbolKind::from_capture(capture_names[capture.index as usize]) else {
+                continue;
+            };
             let node = capture.node;
-            let text = node.utf8_text(code.as_bytes()).unwrap();
-            filed_vec.push((node.start_position().row + 1, text.to_string()));
+            let text = &code[node.byte_range()];
+            // 关键字在匹配时过滤，名称不匹配时只有限定名称的关键字才需要计算限定名称
+            let name_matched = matcher.is_match(text);
+            if !name_matched && !qualified_key {
+                continue;
+            }
+            let qualified_name = get_qualified_name(code, &node, symbol_query.as_ref());
+            if !name_matched && !matcher.is_match(&qualified_name) {
+                continue;
+            }
+            let definition_node = get_definition_node(&node, symbol_query.as_ref());
+            let kind = match definition_node {
+                Some(definition) => get_symbol_kind(kind, &definition, symbol_query.as_ref()),
+                None => kind,
+            };
+            let signature = definition_node
+                .map(|definition| {
+                    to_single_line(&symbol_query.get_definition(code, &definition).to_string())
+                })
+                .filter(|signature| !signature.is_empty())
+                .unwrap_or(text.to_string());
+            let doc = definition_node
+                .and_then(|definition| symbol_query.get_doc_comment(code, &definition));
+            filed_vec.push(Symbol {
+                line: node.start_position().row + 1,
+                name: text.to_string(),
+                qualified_name,
+                kind,
+                signature,
+                doc,
+            });
         }
     }
-    return filed_vec;
+    filed_vec
 }
+/**
+* 大纲的输出选项
+*/
+#[derive(Debug, Clone, Default)]
+pub struct OutlineOptions {
+    /// 最大层级，目录（含根目录）、文件和每层符号各算一层，None 表示不限制
+    pub max_depth: Option<usize>,
+    /// 只显示这些类型的符号，为空时显示全部
+    pub kinds: Vec<SymbolKind>,
+    /// 显示函数体、类体的行数
+    pub show_body_size: bool,
+}
+
+impl OutlineOptions {
+    fn allows_depth(&self, level: usize) -> bool {
+        self.max_depth.is_none_or(|max_depth| level < max_depth)
+    }
+
+    fn allows_kind(&self, kind: Option<SymbolKind>) -> bool {
+        self.kinds.is_empty() || kind.is_some_and(|kind| self.kinds.contains(&kind))
+    }
+}
+
 /**
 * 打印大纲
 */
-pub fn print_outline(code: &String, symbol_query: Box<dyn SymbolQuery>) {
-    let mut parser = Parser::new();
-    parser
-        .set_language(&symbol_query.get_lang())
-        .expect("Error load Rust grammer");
-    let tree = parser.parse(code.as_str(), None).unwrap();
-    let root_node = tree.root_node();
-    recursion_outline(root_node, code, 0, &symbol_query);
+pub fn print_outline(code: &str, symbol_query: &dyn SymbolQuery, options: &OutlineOptions) {
+    for line in format_outline(code, symbol_query, options, 0) {
+        println!("{line}");
+    }
+}
+
+/**
+* 格式化源码的大纲，level 为顶层符号所在的层级
+*/
+fn format_outline(
+    code: &str,
+    symbol_query: &dyn SymbolQuery,
+    options: &OutlineOptions,
+    level: usize,
+) -> Vec<String> {
+    get_outline(code, symbol_query)
+        .into_iter()
+        .filter(|item| options.allows_depth(level + item.depth) && options.allows_kind(item.kind))
+        .map(|item| {
+            let mut range = if item.start_line == item.end_line {
+                item.start_line.to_string()
+            } else {
+                format!("{}-{}", item.start_line, item.end_line)
+            };
+            if let Some(kind) = item.kind {
+                range = format!("{kind} {range}");
+            }
+            match item.body_lines.filter(|_| options.show_body_size) {
+                Some(1) => range.push_str(", 1 line"),
+                Some(body_lines) => range.push_str(&format!(", {body_lines} lines")),
+                None => {}
+            }
+            format!(
+                "{}{} {}",
+                "  ".repeat(level + item.depth),
+                render_definition(&item.definition),
+                format!("[{range}]").bright_black()
+            )
+        })
+        .collect()
+}
+
+/**
+* 打印目录的符号地图：子目录、文件以及每个文件的符号树
+*/
+pub fn print_dir_outline(
+    root_path: &Path,
+    filter: &str,
+    options: &OutlineOptions,
+    encoding: SourceEncoding,
+) {
+    println!("{}", format!("{}/", root_path.display()).blue());
+    // 根目录为第 0 层，其中的子目录和文件从第 1 层开始缩进
+    for line in format_dir_outline(root_path, filter, options, encoding, 1) {
+        println!("{line}");
+    }
+}
+
+/**
+* 格式化目录的大纲，省略隐藏目录和不包含可解析文件的目录
+*/
+fn format_dir_outline(
+    dir: &Path,
+    filter: &str,
+    options: &OutlineOptions,
+    encoding: SourceEncoding,
+    level: usize,
+) -> Vec<String> {
+    let mut lines = vec![];
+    if !options.allows_depth(level) {
+        return lines;
+    }
+    let Ok(entries) = read_dir(dir) else {
+        return lines;
+    };
+    let mut pathes: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .collect();
+    pathes.sort();
+    let indent = "  ".repeat(level);
+    for path in pathes {
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            let children = format_dir_outline(&path, filter, options, encoding, level + 1);
+            // 超出层级的目录没有子项，需要单独判断是否包含可解析的文件
+            if !children.is_empty()
+                || (!options.allows_depth(level + 1) && contains_outline_file(&path, filter))
+            {
+                lines.push(format!("{indent}{}", format!("{name}/").blue()));
+                lines.extend(children);
+            }
+        } else if is_outline_file(&path, filter) {
+            let extension = path.extension().and_then(|ext| ext.to_str()).unwrap();
+            let symbol_query = get_symbol_query(extension);
+            let symbols = if options.allows_depth(level + 1) {
+                let code = read_source(&path, encoding).unwrap_or_default();
+                format_outline(&code, symbol_query.as_ref(), options, level + 1)
+            } else {
+                vec![]
+            };
+            // 按类型过滤时，省略没有匹配符号的文件
+            if symbols.is_empty() && !options.kinds.is_empty() {
+                continue;
+            }
+            lines.push(format!("{indent}{}", name.green()));
+            lines.extend(symbols);
+        }
+    }
+    lines
+}
+
+/**
+* 是否为可以生成大纲的文件
+*/
+fn is_outline_file(path: &Path, filter: &str) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| (filter.is_empty() || filter == ext) && support_symbol(ext))
+}
+
+fn contains_outline_file(dir: &Path, filter: &str) -> bool {
+    let mut pathes = vec![];
+    recursion_dir(dir, &mut pathes, filter);
+    pathes.iter().any(|path| is_outline_file(path, filter))
+}
+
+/**
+* 大纲中的一项
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutlineItem {
+    /// 嵌套深度，顶层为 0
+    pub depth: usize,
+    /// 查询未覆盖的大纲节点（如字段）没有类型
+    pub kind: Option<SymbolKind>,
+    pub definition: Definition,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// 函数体、类体等的行数
+    pub body_lines: Option<usize>,
+}
+
+/**
+* 获取源码的大纲
+*/
+pub fn get_outline(code: &str, symbol_query: &dyn SymbolQuery) -> Vec<OutlineItem> {
+    let Some(tree) = parse_code(code, symbol_query) else {
+        return vec![];
+    };
+    let kinds = get_outline_kinds(code, &tree, symbol_query);
+    let mut outline = vec![];
+    recursion_outline(tree.root_node(), code, symbol_query, &kinds, &mut outline);
+    outline
 }
 
-pub fn recursion_outline(
+/**
+* 通过符号查询得到定义节点的类型，键为定义节点的 id
+*/
+fn get_outline_kinds(
+    code: &str,
+    tree: &Tree,
+    symbol_query: &dyn SymbolQuery,
+) -> HashMap<usize, SymbolKind> {
+    let mut query_cursor = QueryCursor::new();
+    let mut kinds = HashMap::new();
+    for query in get_compiled_queries(symbol_query).iter() {
+        let capture_names = query.capture_names();
+        for (m, capture_index) in query_cursor.captures(query, tree.root_node(), code.as_bytes()) {
+            let capture = m.captures[capture_index];
+            let Some(kind) = SymbolKind::from_capture(capture_names[capture.index as usize]) else {
+                continue;
+            };
+            if let Some(definition_node) = get_definition_node(&capture.node, symbol_query) {
+                let kind = get_symbol_kind(kind, &definition_node, symbol_query);
+                kinds.insert(definition_node.id(), kind);
+            }
+        }
+    }
+    kinds
+}
+
+/**
+* 大纲节点的层级，即上一级节点中大纲节点的个数
+*/
+fn get_outline_depth(node: &Node, symbol_query: &dyn SymbolQuery) -> usize {
+    iter::successors(symbol_query.get_parent(node), |parent| {
+        symbol_query.get_parent(parent)
+    })
+    .filter(|parent| symbol_query.is_key_node(parent))
+    .count()
+}
+
+fn recursion_outline(
     node: Node,
-    code: &String,
-    indent: usize,
-    symbol_query: &Box<dyn SymbolQuery>,
+    code: &str,
+    symbol_query: &dyn SymbolQuery,
+    kinds: &HashMap<usize, SymbolKind>,
+    outline: &mut Vec<OutlineItem>,
 ) {
-    let mut temp_indent = indent;
     if symbol_query.is_key_node(&node) {
-        print!("{}", " ".repeat(indent));
-        let output = symbol_query.get_definition(code, &node);
-        println!("{}", output);
-        temp_indent += 2;
+        // 带装饰器的定义（Python 的 decorated_definition）从内部的 definition 中获取函数体
+        let body_lines = node
+            .child_by_field_name("body")
+            .or_else(|| {
+                node.child_by_field_name("definition")
+                    .and_then(|definition| definition.child_by_field_name("body"))
+            })
+            .map(|body| body.end_position().row - body.start_position().row + 1);
+        outline.push(OutlineItem {
+            depth: get_outline_depth(&node, symbol_query),
+            kind: kinds.get(&node.id()).copied(),
+            definition: symbol_query.get_definition(code, &node),
+            start_line: node.start_position().row + 1,
+            end_line: symbol_query.get_end_line(&node),
+            body_lines,
+        });
     }
 
     for child in node.children(&mut node.walk()) {
-        recursion_outline(child, code, temp_indent, symbol_query)
+        recursion_outline(child, code, symbol_query, kinds, outline)
+    }
+}
+
+/// 不小于该大小的文件使用内存映射读取
+const MMAP_THRESHOLD: u64 = 1024 * 1024;
+/// 二进制检测只检查文件开头的字节
+const BINARY_PROBE_SIZE: usize = 8192;
+
+/**
+* 文件内容，大文件为内存映射，小文件直接读入内存
+*/
+enum FileBytes {
+    Mapped(Mmap),
+    Owned(Vec<u8>),
+}
+
+impl Deref for FileBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            FileBytes::Mapped(mmap) => mmap,
+            FileBytes::Owned(bytes) => bytes,
+        }
+    }
+}
+
+fn read_file_bytes(path: &Path) -> Result<FileBytes, std::io::Error> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    if len >= MMAP_THRESHOLD {
+        // 与 ripgrep 相同，接受映射期间文件被其他进程截断的风险
+        let mmap = unsafe { Mmap::map(&file)? };
+        return Ok(FileBytes::Mapped(mmap));
     }
+    let mut bytes = Vec::with_capacity(len as usize);
+    file.read_to_end(&mut bytes)?;
+    Ok(FileBytes::Owned(bytes))
 }
 
+/**
+* 文件开头包含 NUL 字节时视为二进制文件
+*/
+pub fn is_binary(bytes: &[u8]) -> bool {
+    memchr::memchr(0, &bytes[..bytes.len().min(BINARY_PROBE_SIZE)]).is_some()
+}
+
+/**
+* 逐行查找文件中包含关键字（或匹配正则表达式）的行
+*
+* 带 BOM 或指定了编码（encoding）时先转码再匹配，行号与原文件一致；
+* 二进制文件返回 `ErrorKind::InvalidData` 错误；非 UTF-8 的字节按替换字符处理，其余内容照常匹配
+*/
 pub fn find_text_in_file(
     filename: &str,
     text: &str,
     reg: Option<Rc<Regex>>,
+    encoding: SourceEncoding,
 ) -> Result<Vec<(usize, String)>, std::io::Error> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
-    let mut found_lines = Vec::new();
-
-    for (line_number, line) in reader.lines().enumerate() {
-        let line = line.unwrap_or("".to_string());
-        if reg.is_some() {
-            let reg = reg.clone().unwrap();
-            if reg.captures(line.as_str()).is_some() {
-                found_lines.push((line_number + 1, line));
+    with_text_bytes(filename, encoding, |bytes| {
+        // 字面量关键字先在整个文件中查找，不包含时无需逐行处理
+        if reg.is_none() && memchr::memmem::find(bytes, text.as_bytes()).is_none() {
+            return vec![];
+        }
+        let mut found_lines = Vec::new();
+        if bytes.is_empty() {
+            return found_lines;
+        }
+        let content = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+        for (line_number, line) in content.split(|byte| *byte == b'\n').enumerate() {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            let line = String::from_utf8_lossy(line);
+            let matched = match &reg {
+                Some(reg) => reg.is_match(&line),
+                None => line.contains(text),
+            };
+            if matched {
+                found_lines.push((line_number + 1, line.into_owned()));
+            }
+        }
+        found_lines
+    })
+}
+
+/**
+* 多模式搜索中匹配的一行
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternLine {
+    pub line: usize,
+    pub text: String,
+    /// 每处匹配的模式下标及其在行中的字节范围
+    pub matches: Vec<(usize, Range<usize>)>,
+}
+
+impl PatternLine {
+    /**
+     * 该行匹配到的模式下标，去重并保持出现顺序
+     */
+    pub fn patterns(&self) -> Vec<usize> {
+        let mut patterns = vec![];
+        for (pattern, _) in &self.matches {
+            if !patterns.contains(pattern) {
+                patterns.push(*pattern);
+            }
+        }
+        patterns
+    }
+}
+
+/**
+* 构建多模式搜索的自动机，重叠时优先最长的模式
+*/
+pub fn build_pattern_automaton(patterns: &[String]) -> Result<AhoCorasick, String> {
+    AhoCorasick::builder()
+        .match_kind(MatchKind::LeftmostLongest)
+        .build(patterns)
+        .map_err(|err| err.to_string())
+}
+
+/**
+* 读取模式文件，每行一个模式，忽略空行
+*/
+pub fn read_patterns_file(
+    path: &Path,
+    encoding: SourceEncoding,
+) -> Result<Vec<String>, std::io::Error> {
+    Ok(read_source(path, encoding)?
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/**
+* 使用 Aho-Corasick 自动机同时查找多个固定字符串，如一组废弃的标识符
+*/
+pub fn find_patterns_in_file(
+    filename: &str,
+    automaton: &AhoCorasick,
+    encoding: SourceEncoding,
+) -> Result<Vec<PatternLine>, std::io::Error> {
+    with_text_bytes(filename, encoding, |bytes| {
+        let mut found_lines = vec![];
+        if !automaton.is_match(bytes) {
+            return found_lines;
+        }
+        let content = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+        for (line_number, line) in content.split(|byte| *byte == b'\n').enumerate() {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            let line = String::from_utf8_lossy(line);
+            let matches: Vec<(usize, Range<usize>)> = automaton
+                .find_iter(line.as_ref())
+                .map(|found| (found.pattern().as_usize(), found.range()))
+                .collect();
+            if !matches.is_empty() {
+                found_lines.push(PatternLine {
+                    line: line_number + 1,
+                    text: line.into_owned(),
+                    matches,
+                });
             }
-        } else if line.contains(text) {
-            found_lines.push((line_number + 1, line));
         }
+        found_lines
+    })
+}
+
+/**
+* 跨行匹配的结果
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultilineMatch {
+    pub start_line: usize,
+    pub end_line: usize,
+    /// 完整的匹配内容
+    pub text: String,
+}
+
+/**
+* 在整个文件上执行正则表达式，匹配内容可以跨行，如 `#[derive\(.*\)]\s*struct`
+*/
+pub fn find_multiline_in_file(
+    filename: &str,
+    reg: &Regex,
+    encoding: SourceEncoding,
+) -> Result<Vec<MultilineMatch>, std::io::Error> {
+    with_text_bytes(filename, encoding, |bytes| {
+        let content = String::from_utf8_lossy(bytes);
+        let mut matches = vec![];
+        // 增量统计换行数，避免每个匹配都从文件开头计算行号
+        let mut line = 1;
+        let mut offset = 0;
+        for found in reg.find_iter(&content) {
+            if found.is_empty() {
+                continue;
+            }
+            line += memchr::memchr_iter(b'\n', &content.as_bytes()[offset..found.start()]).count();
+            let matched = found.as_str().trim_end_matches(['\r', '\n']);
+            matches.push(MultilineMatch {
+                start_line: line,
+                end_line: line + memchr::memchr_iter(b'\n', matched.as_bytes()).count(),
+                text: matched.to_string(),
+            });
+            offset = found.start();
+        }
+        matches
+    })
+}
+
+/**
+* 读取文件用于文本搜索：带 BOM 或指定了编码（encoding）时先转码，二进制文件返回 `ErrorKind::InvalidData` 错误
+*/
+fn with_text_bytes<T>(
+    filename: &str,
+    encoding: SourceEncoding,
+    search: impl FnOnce(&[u8]) -> T,
+) -> Result<T, std::io::Error> {
+    let bytes = read_file_bytes(Path::new(filename))?;
+    // UTF-16 等编码包含 NUL 字节，需要先转码再检测二进制
+    let decoded;
+    let bytes: &[u8] = if needs_transcoding(&bytes, encoding) {
+        decoded = decode_source(&bytes, encoding);
+        decoded.as_bytes()
+    } else {
+        &bytes
+    };
+    if is_binary(bytes) {
+        return Err(std::io::Error::new(ErrorKind::InvalidData, "binary file"));
     }
-    Ok(found_lines)
+    Ok(search(bytes))
 }
 
 pub fn get_absolute_path(path: &Path) -> String {
     if path.exists() {
         let absolute_path = fs::canonicalize(path).unwrap();
-        return absolute_path.to_str().unwrap().to_string();
+        absolute_path.to_str().unwrap().to_string()
     } else {
-        return String::new();
+        String::new()
     }
 }
 
@@ -279,12 +1084,16 @@ pub struct CodeIndex {
     pub path: String,
     pub line: usize,
     pub line_code: String,
+    pub qualified_name: String,
+    pub kind: SymbolKind,
+    pub signature: String,
+    pub doc: Option<String>,
 }
 
 /**
 * 构建索引
 */
-pub fn build_index(project_path: &Path) -> Vec<CodeIndex> {
+pub fn build_index(project_path: &Path, encoding: SourceEncoding) -> Vec<CodeIndex> {
     let mut index_list = vec![];
     let mut pathes = vec![];
     // 获取项目中的文件
@@ -295,31 +1104,718 @@ pub fn build_index(project_path: &Path) -> Vec<CodeIndex> {
         ProgressStyle::with_template("{spinner:.green} {pos}/{len} [{elapsed_precise}] {msg}")
             .unwrap(),
     );
-    let mut progress = 1;
-    if pathes.len() > 0 {
-        for path in pathes {
+    if !pathes.is_empty() {
+        for (progress, path) in (1..).zip(pathes) {
+            pb.set_position(progress);
             let path_extension = path.extension().unwrap().to_str().unwrap();
+            // 没有符号查询的文件不必解析，否则会退回用 Rust 语法解析
+            if !support_symbol(path_extension) {
+                continue;
+            }
             let path_str = get_absolute_path(&path);
 
-            let code = fs::read_to_string(Path::new(path_str.as_str())).unwrap_or("".to_string());
-            let result = get_all_symbols(&code, ".*", get_symbol_query(path_extension));
-            if result.len() > 0 {
+            let code = read_source(path_str.as_str(), encoding).unwrap_or("".to_string());
+            let result = get_all_symbols(
+                &code,
+                ".*",
+                SymbolMatch::Regex,
+                get_symbol_query(path_extension),
+            );
+            if !result.is_empty() {
                 result
                     .iter()
                     .map(|item| CodeIndex {
                         path: path_str.to_string(),
-                        line: item.0,
-                        line_code: item.1.clone(),
+                        line: item.line,
+                        line_code: item.name.clone(),
+                        qualified_name: item.qualified_name.clone(),
+                        kind: item.kind,
+                        signature: item.signature.clone(),
+                        doc: item.doc.clone(),
                     })
                     .for_each(|item| {
                         pb.set_message(item.path.clone());
-                        pb.set_position(progress);
                         index_list.push(item);
                     });
             }
-            progress += 1;
         }
     }
     pb.with_finish(ProgressFinish::AndClear);
-    return index_list;
+    index_list
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::RegexBuilder;
+
+    const RUST_CODE: &str = "fn get_name() {}\nfn set_name() {}\nstruct Named;\n";
+
+    /**
+     * 测试用的索引项，名称取限定名称的最后一段
+     */
+    pub(crate) fn code_index(path: &str, qualified_name: &str, kind: SymbolKind) -> CodeIndex {
+        CodeIndex {
+            path: path.to_string(),
+            line: 1,
+            line_code: qualified_name
+                .rsplit(['.', ':'])
+                .next()
+                .unwrap()
+                .to_string(),
+            qualified_name: qualified_name.to_string(),
+            kind,
+            signature: String::new(),
+            doc: None,
+        }
+    }
+
+    /**
+     * 测试用的临时文件，文件名带上进程号避免冲突，离开作用域时删除
+     */
+    pub(crate) struct TempFile(PathBuf);
+
+    impl TempFile {
+        pub(crate) fn new(name: &str, contents: impl AsRef<[u8]>) -> TempFile {
+            let path =
+                std::env::temp_dir().join(format!("code-search-{}-{name}", std::process::id()));
+            fs::write(&path, contents).unwrap();
+            TempFile(path)
+        }
+
+        pub(crate) fn path(&self) -> &str {
+            self.0.to_str().unwrap()
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    /**
+     * 测试用的临时目录，离开作用域时连同其中的文件一起删除
+     */
+    pub(crate) struct TempDir(PathBuf);
+
+    impl TempDir {
+        pub(crate) fn new(name: &str, files: &[(&str, &str)]) -> TempDir {
+            let path =
+                std::env::temp_dir().join(format!("code-search-{}-{name}", std::process::id()));
+            fs::create_dir_all(&path).unwrap();
+            for (file, contents) in files {
+                fs::write(path.join(file), contents).unwrap();
+            }
+            TempDir(path)
+        }
+
+        pub(crate) fn path(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_symbol_match_modes() {
+        let literal = get_all_symbols(RUST_CODE, "name", SymbolMatch::Literal, Box::new(RustQuery));
+        assert_eq!(literal.len(), 2);
+
+        let prefix = get_all_symbols(RUST_CODE, "get", SymbolMatch::Prefix, Box::new(RustQuery));
+        assert_eq!(prefix.len(), 1);
+        assert_eq!((prefix[0].line, prefix[0].name.as_str()), (1, "get_name"));
+
+        let regex = get_all_symbols(RUST_CODE, "^N.*d$", SymbolMatch::Regex, Box::new(RustQuery));
+        assert_eq!(regex.len(), 1);
+        assert_eq!((regex[0].line, regex[0].name.as_str()), (3, "Named"));
+    }
+
+    #[test]
+    fn test_symbol_key_escaped() {
+        for key in ["\"", "\\", "a\"b\\c", "(", "get_name.*"] {
+            let result = get_all_symbols(RUST_CODE, key, SymbolMatch::Literal, Box::new(RustQuery));
+            assert!(result.is_empty());
+        }
+        // 非法的正则表达式不会崩溃
+        assert!(
+            get_all_symbols(RUST_CODE, "(", SymbolMatch::Regex, Box::new(RustQuery)).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_symbol_kind() {
+        let symbols = get_all_symbols(RUST_CODE, "", SymbolMatch::Literal, Box::new(RustQuery));
+        let kinds: Vec<SymbolKind> = symbols.iter().map(|symbol| symbol.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                SymbolKind::Function,
+                SymbolKind::Function,
+                SymbolKind::Struct
+            ]
+        );
+        assert_eq!(
+            parse_symbol_kinds("fn, class").unwrap(),
+            vec![SymbolKind::Function, SymbolKind::Class]
+        );
+        assert!(parse_symbol_kinds("fn,unknown").is_err());
+    }
+
+    #[test]
+    fn test_qualified_name() {
+        let code = "mod hint {\n    impl Hinter for CodeHinter {\n        fn hint() {}\n    }\n}\n";
+        let symbols = get_all_symbols(code, "hint", SymbolMatch::Literal, Box::new(RustQuery));
+        let names: Vec<&str> = symbols
+            .iter()
+            .map(|symbol| symbol.qualified_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["hint::CodeHinter::hint", "hint"]);
+
+        let code = fs::read_to_string("examples/main.go").unwrap();
+        let symbols = get_all_symbols(
+            &code,
+            "Rectangle.Area",
+            SymbolMatch::Literal,
+            Box::new(GoQuery),
+        );
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].qualified_name, "Rectangle.Area");
+    }
+
+    #[test]
+    fn test_method_kinds() {
+        let kinds = |code: &str, symbol_query: Box<dyn SymbolQuery>| -> Vec<(String, SymbolKind)> {
+            get_all_symbols(code, "", SymbolMatch::Literal, symbol_query)
+                .into_iter()
+                .map(|symbol| (symbol.qualified_name, symbol.kind))
+                .collect()
+        };
+        let rust = "mod m { fn free() { fn inner() {} } }\nimpl S { fn new() {} }\ntrait T { type Item; fn next(&self); }\nextern \"C\" { fn abs(x: i32) -> i32; }\n";
+        let rust = kinds(rust, Box::new(RustQuery));
+        assert!(rust.contains(&("m::free".to_string(), SymbolKind::Function)));
+        assert!(rust.contains(&("m::inner".to_string(), SymbolKind::Function)));
+        assert!(rust.contains(&("S::new".to_string(), SymbolKind::Method)));
+        assert!(rust.contains(&("T::Item".to_string(), SymbolKind::Type)));
+        assert!(rust.contains(&("T::next".to_string(), SymbolKind::Method)));
+        assert!(rust.contains(&("abs".to_string(), SymbolKind::Function)));
+
+        let python = "class A:\n    def run(self):\n        def helper():\n            pass\n\ndef main():\n    pass\n";
+        let python = kinds(python, Box::new(PythonQuery));
+        assert!(python.contains(&("A.run".to_string(), SymbolKind::Method)));
+        assert!(python.contains(&("A.helper".to_string(), SymbolKind::Function)));
+        assert!(python.contains(&("main".to_string(), SymbolKind::Function)));
+
+        // 按节点类型区分，类型名以 struct、interface 开头的不能被当作结构体或接口
+        let go = "package p\ntype A struct{}\ntype B interface{}\ntype C structpb.Struct\ntype D interfaces.Foo\ntype E []int\n";
+        let go = kinds(go, Box::new(GoQuery));
+        assert_eq!(
+            go,
+            vec![
+                ("A".to_string(), SymbolKind::Struct),
+                ("B".to_string(), SymbolKind::Interface),
+                ("C".to_string(), SymbolKind::Type),
+                ("D".to_string(), SymbolKind::Type),
+                ("E".to_string(), SymbolKind::Type),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_member_symbols() {
+        let code = "struct Shape {\n    width: f64,\n}\nenum Color {\n    Red,\n}\nconst MAX: usize = 1;\n";
+        let symbols = get_all_symbols(code, "", SymbolMatch::Literal, Box::new(RustQuery));
+        let members: Vec<_> = symbols
+            .iter()
+            .filter(|symbol| symbol.kind != SymbolKind::Struct && symbol.kind != SymbolKind::Enum)
+            .map(|symbol| {
+                (
+                    symbol.kind,
+                    symbol.qualified_name.as_str(),
+                    symbol.signature.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            members,
+            vec![
+                (SymbolKind::Field, "Shape::width", "width : f64"),
+                (SymbolKind::Variant, "Color::Red", "Red"),
+                (SymbolKind::Constant, "MAX", "const MAX : usize"),
+            ]
+        );
+
+        let code = "class Rect {\n    public int Width { get; set; }\n}\n";
+        let symbols = get_all_symbols(code, "Width", SymbolMatch::Literal, Box::new(CSharpQuery));
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(
+            (symbols[0].kind, symbols[0].qualified_name.as_str()),
+            (SymbolKind::Field, "Rect.Width")
+        );
+    }
+
+    #[test]
+    fn test_find_text_in_file() {
+        let latin = TempFile::new("latin.txt", b"caf\xe9 match\r\nplain\nlast match");
+        let lines =
+            find_text_in_file(latin.path(), "match", None, SourceEncoding::default()).unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                (1, "caf\u{fffd} match".to_string()),
+                (3, "last match".to_string())
+            ]
+        );
+        let regex = Rc::new(Regex::new("^pl").unwrap());
+        let lines =
+            find_text_in_file(latin.path(), "", Some(regex), SourceEncoding::default()).unwrap();
+        assert_eq!(lines, vec![(2, "plain".to_string())]);
+
+        let binary = TempFile::new("binary.txt", b"ELF\x00match");
+        let err =
+            find_text_in_file(binary.path(), "match", None, SourceEncoding::default()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_find_multiline_in_file() {
+        let file = TempFile::new(
+            "multiline.rs",
+            "use std::fmt;\n\n#[derive(Debug)]\nstruct Shape;\n#[derive(Clone)]\nenum Kind {}\n",
+        );
+        let regex = RegexBuilder::new(r"^#\[derive\(.*\)\]\s*struct \w+")
+            .multi_line(true)
+            .build()
+            .unwrap();
+        let matches =
+            find_multiline_in_file(file.path(), &regex, SourceEncoding::default()).unwrap();
+        assert_eq!(
+            matches,
+            vec![MultilineMatch {
+                start_line: 3,
+                end_line: 4,
+                text: "#[derive(Debug)]\nstruct Shape".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_find_patterns_in_file() {
+        let file = TempFile::new(
+            "patterns.go",
+            "type Rectangle struct {}\nfunc (r Rectangle) Area() float64 {\n\treturn 0\n}\n",
+        );
+        let patterns = vec![
+            "Area".to_string(),
+            "Rect".to_string(),
+            "Rectangle".to_string(),
+        ];
+        let automaton = build_pattern_automaton(&patterns).unwrap();
+        let lines =
+            find_patterns_in_file(file.path(), &automaton, SourceEncoding::default()).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].line, 1);
+        // 同一位置优先匹配最长的模式
+        assert_eq!(lines[0].matches, vec![(2, 5..14)]);
+        assert_eq!(lines[1].line, 2);
+        assert_eq!(lines[1].patterns(), vec![2, 0]);
+    }
+
+    #[test]
+    fn test_compiled_queries() {
+        for extension in [
+            "rs", "java", "py", "c", "cpp", "cs", "js", "go", "md", "json", "yaml", "toml",
+        ] {
+            let symbol_query = get_symbol_query(extension);
+            let queries = get_compiled_queries(symbol_query.as_ref());
+            // 所有查询都能编译，且同一语言只编译一次
+            assert_eq!(
+                queries.len(),
+                symbol_query.get_queries().len(),
+                "{extension}"
+            );
+            assert!(Rc::ptr_eq(
+                &queries,
+                &get_compiled_queries(symbol_query.as_ref())
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let code = "fn broken( {\n    let x = ;\n}\n\nstruct After;\n";
+        let errors = find_parse_errors(code, &RustQuery);
+        let locations: Vec<_> = errors
+            .iter()
+            .map(|error| (error.line, error.column, error.label(), error.text.as_str()))
+            .collect();
+        assert_eq!(
+            locations,
+            vec![(1, 11, "MISSING", ")"), (2, 11, "ERROR", "=")]
+        );
+        // 语法错误之后的符号仍然可以提取
+        let symbols = get_all_symbols(code, "After", SymbolMatch::Literal, Box::new(RustQuery));
+        assert_eq!(symbols.len(), 1);
+        assert!(find_parse_errors(RUST_CODE, &RustQuery).is_empty());
+    }
+
+    #[test]
+    fn test_symbol_signature_and_doc() {
+        let code = "/// 计算面积\n#[inline]\npub fn area(width: f64) -> f64 { width }\n\n// 无关注释\n\nstruct Shape;\n";
+        let symbols = get_all_symbols(code, "", SymbolMatch::Literal, Box::new(RustQuery));
+        assert_eq!(symbols[0].signature, "pub fn area (width: f64) -> f64");
+        assert_eq!(symbols[0].doc.as_deref(), Some("计算面积"));
+        assert_eq!(symbols[1].doc, None);
+
+        let code = "class Shape:\n    \"\"\"A shape.\n\n    Has an area.\n    \"\"\"\n";
+        let symbols = get_all_symbols(code, "Shape", SymbolMatch::Literal, Box::new(PythonQuery));
+        assert_eq!(symbols[0].doc.as_deref(), Some("A shape.\n\nHas an area."));
+    }
+
+    #[test]
+    fn test_outline_items() {
+        let code =
+            "struct Shape;\n\nimpl Shape {\n    fn area(&self) -> f64 {\n        1.0\n    }\n}\n";
+        let outline = get_outline(code, &RustQuery);
+        let items: Vec<_> = outline
+            .iter()
+            .map(|item| {
+                (
+                    item.depth,
+                    item.kind,
+                    item.start_line,
+                    item.end_line,
+                    item.body_lines,
+                )
+            })
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                (0, Some(SymbolKind::Struct), 1, 1, None),
+                (0, None, 3, 7, Some(5)),
+                (1, Some(SymbolKind::Method), 4, 6, Some(3)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_outline_fixtures() {
+        ColorChoice::Never.apply();
+        let cases = [
+            ("outline.rs", "  pub enum Color [enum 24-27]"),
+            ("outline.rs", "macro_rules! square [macro 45-49]"),
+            (
+                "Outline.java",
+                "  public Outline (String name) [method 13-15]",
+            ),
+            ("Outline.java", "  RED [variant 33]"),
+            ("outline.py", "  @staticmethod def create () [method 28-30]"),
+            ("outline.js", "const square (x) [fn 8-10]"),
+            ("outline.c", "typedef struct Person [type 24-26]"),
+            ("outline.cpp", "namespace geometry [mod 6-34]"),
+            ("Outline.cs", "    public string Name [field 26]"),
+            ("outline.go", "  Width, Height float64 [field 22]"),
+        ];
+        for (file, expected) in cases {
+            let path = format!("examples/fixtures/{file}");
+            let code = fs::read_to_string(&path).unwrap();
+            let extension = file.rsplit('.').next().unwrap();
+            let symbol_query = get_symbol_query(extension);
+            let lines = format_outline(&code, symbol_query.as_ref(), &OutlineOptions::default(), 0);
+            assert!(
+                lines.iter().any(|line| line == expected),
+                "{file}: {expected:?} not in {lines:#?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_dir_outline() {
+        ColorChoice::Never.apply();
+        let options = OutlineOptions {
+            max_depth: Some(3),
+            kinds: vec![SymbolKind::Interface],
+            show_body_size: false,
+        };
+        let lines = format_dir_outline(
+            Path::new("examples"),
+            "",
+            &options,
+            SourceEncoding::default(),
+            1,
+        );
+        assert_eq!(
+            lines,
+            vec![
+                "  Main.java",
+                "    interface Printable [interface 39-41]",
+                "  main.go",
+                "    type Shape interface [interface 8-11]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_index_headers() {
+        let dir = TempDir::new(
+            "headers",
+            &[
+                ("shape.h", "struct Shape { int width; };\n"),
+                ("shape.hpp", "class Canvas {\n    void draw() {}\n};\n"),
+            ],
+        );
+        let mut symbols: Vec<(String, String, SymbolKind)> =
+            build_index(dir.path(), SourceEncoding::default())
+                .into_iter()
+                .map(|item| {
+                    let file = Path::new(&item.path).file_name().unwrap();
+                    (
+                        file.to_str().unwrap().to_string(),
+                        item.qualified_name,
+                        item.kind,
+                    )
+                })
+                .collect();
+        symbols.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+        let expected = [
+            ("shape.h", "Shape", SymbolKind::Struct),
+            ("shape.h", "Shape.width", SymbolKind::Field),
+            ("shape.hpp", "Canvas", SymbolKind::Class),
+            ("shape.hpp", "Canvas::draw", SymbolKind::Method),
+        ];
+        assert_eq!(
+            symbols,
+            expected.map(|(file, name, kind)| (file.to_string(), name.to_string(), kind))
+        );
+    }
+
+    #[test]
+    fn test_markdown_headings() {
+        let code = "# Guide\n\nIntro\n\n## Install\n\n```\n# not a heading\n```\n\nUsage\n-----\n";
+        let symbols = get_all_symbols(code, "", SymbolMatch::Literal, Box::new(MarkdownQuery));
+        let names: Vec<_> = symbols
+            .iter()
+            .map(|symbol| (symbol.line, symbol.qualified_name.as_str(), symbol.kind))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (1, "Guide", SymbolKind::Heading),
+                (5, "Guide > Install", SymbolKind::Heading),
+                (11, "Guide > Usage", SymbolKind::Heading),
+            ]
+        );
+
+        let outline = get_outline(code, &MarkdownQuery);
+        let items: Vec<_> = outline
+            .iter()
+            .map(|item| {
+                (
+                    item.depth,
+                    item.definition.to_string(),
+                    item.start_line,
+                    item.end_line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                (0, "# Guide".to_string(), 1, 12),
+                (1, "## Install".to_string(), 5, 10),
+                (1, "## Usage".to_string(), 11, 12),
+            ]
+        );
+
+        // ATX 标题嵌套在之前更高级的 setext 标题之下
+        let code = "Title\n=====\n\n## Sub\n\n### Deep\n\nOther\n=====\n";
+        let mut names: Vec<_> =
+            get_all_symbols(code, "", SymbolMatch::Literal, Box::new(MarkdownQuery))
+                .into_iter()
+                .map(|symbol| (symbol.line, symbol.qualified_name))
+                .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                (1, "Title".to_string()),
+                (4, "Title > Sub".to_string()),
+                (6, "Title > Sub > Deep".to_string()),
+                (8, "Other".to_string()),
+            ]
+        );
+        let lines: Vec<_> = get_outline(code, &MarkdownQuery)
+            .iter()
+            .map(|item| (item.depth, item.start_line, item.end_line))
+            .collect();
+        assert_eq!(lines, vec![(0, 1, 7), (1, 4, 7), (2, 6, 7), (0, 8, 9)]);
+    }
+
+    #[test]
+    fn test_config_key_paths() {
+        let key_paths = |code: &str, symbol_query: Box<dyn SymbolQuery>| -> Vec<String> {
+            get_all_symbols(code, "", SymbolMatch::Literal, symbol_query)
+                .into_iter()
+                .map(|symbol| symbol.qualified_name)
+                .collect()
+        };
+
+        let toml = "[dependencies]\ntree-sitter = \"0.23\"\n\n[[bin]]\nname = \"a\"\n\n[[bin]]\nname = \"b\"\n";
+        assert_eq!(
+            key_paths(toml, Box::new(TomlQuery)),
+            vec![
+                "dependencies",
+                "bin[0]",
+                "bin[1]",
+                "dependencies.tree-sitter",
+                "bin[0].name",
+                "bin[1].name"
+            ]
+        );
+
+        let yaml = "spec:\n  containers:\n    - name: web\n      image: nginx\n";
+        assert_eq!(
+            key_paths(yaml, Box::new(YamlQuery)),
+            vec![
+                "spec",
+                "spec.containers",
+                "spec.containers[0].name",
+                "spec.containers[0].image"
+            ]
+        );
+
+        let json = r#"{"spec": {"containers": [{"image": "a"}, {"image": "b"}]}}"#;
+        let symbols = get_all_symbols(
+            json,
+            "containers[1].image",
+            SymbolMatch::Literal,
+            Box::new(JsonQuery),
+        );
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].qualified_name, "spec.containers[1].image");
+        assert_eq!(symbols[0].signature, "image: \"b\"");
+    }
+
+    #[test]
+    fn test_definition_tokens() {
+        let code = "pub fn area(width: f64) -> f64 { width }\n";
+        let tree = parse_code(code, &RustQuery).unwrap();
+        let node = tree.root_node().named_child(0).unwrap();
+        let kinds: Vec<TokenKind> = RustQuery
+            .get_definition(code, &node)
+            .tokens
+            .iter()
+            .map(|token| token.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Keyword,
+                TokenKind::Keyword,
+                TokenKind::Name,
+                TokenKind::Params,
+                TokenKind::Text,
+                TokenKind::ReturnType
+            ]
+        );
+
+        ColorChoice::Never.apply();
+        let definition = RustQuery.get_definition(code, &node);
+        assert_eq!(render_definition(&definition), definition.to_string());
+        assert!(!render_definition(&definition).contains('\x1b'));
+    }
+
+    /**
+     * 生成 golden 文件内容：所有符号（行号、类型、限定名称、签名）和文件大纲
+     */
+    fn format_golden(code: &str, extension: &str) -> String {
+        let mut symbols =
+            get_all_symbols(code, "", SymbolMatch::Literal, get_symbol_query(extension));
+        symbols.sort_by_key(|symbol| symbol.line);
+        let mut lines = vec![String::from("## symbols")];
+        for symbol in symbols {
+            lines.push(format!(
+                "{} {} {} | {}",
+                symbol.line,
+                symbol.kind.as_str(),
+                symbol.qualified_name,
+                symbol.signature
+            ));
+        }
+        lines.push(String::from("## outline"));
+        let options = OutlineOptions {
+            show_body_size: true,
+            ..OutlineOptions::default()
+        };
+        lines.extend(format_outline(
+            code,
+            get_symbol_query(extension).as_ref(),
+            &options,
+            0,
+        ));
+        lines.join("\n") + "\n"
+    }
+
+    fn collect_golden_sources(dir: &Path, files: &mut Vec<PathBuf>) {
+        let mut entries: Vec<PathBuf> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        entries.sort();
+        for path in entries {
+            if path.is_dir() {
+                collect_golden_sources(&path, files);
+            } else if path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(support_symbol)
+            {
+                files.push(path);
+            }
+        }
+    }
+
+    /**
+     * 对 examples 下的每个文件比对 tests/golden 中的期望输出，防止语法升级悄悄改变符号提取结果
+     *
+     * 设置环境变量 `UPDATE_GOLDEN=1` 重新生成期望输出
+     */
+    #[test]
+    fn test_golden_files() {
+        ColorChoice::Never.apply();
+        let update = std::env::var("UPDATE_GOLDEN").is_ok_and(|value| value == "1");
+        let mut sources = vec![];
+        collect_golden_sources(Path::new("examples"), &mut sources);
+        let mut mismatches = vec![];
+        for source in sources {
+            let extension = source.extension().unwrap().to_str().unwrap();
+            let code = fs::read_to_string(&source).unwrap();
+            let actual = format_golden(&code, extension);
+            let relative = source.strip_prefix("examples").unwrap();
+            let golden = Path::new("tests/golden").join(format!("{}.txt", relative.display()));
+            if update {
+                fs::create_dir_all(golden.parent().unwrap()).unwrap();
+                fs::write(&golden, &actual).unwrap();
+                continue;
+            }
+            match fs::read_to_string(&golden) {
+                Ok(expected) if expected == actual => {}
+                Ok(expected) => mismatches.push(format!(
+                    "{}\n--- expected\n{expected}--- actual\n{actual}",
+                    golden.display()
+                )),
+                Err(_) => mismatches.push(format!("{} 不存在", golden.display())),
+            }
+        }
+        assert!(
+            mismatches.is_empty(),
+            "golden 文件不一致，确认无误后使用 UPDATE_GOLDEN=1 更新：\n{}",
+            mismatches.join("\n")
+        );
+    }
 }
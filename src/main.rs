@@ -1,7 +1,13 @@
-use clap::{arg, ArgMatches, Command};
+use clap::{arg, ArgAction, ArgMatches, Command};
 use code_search::{
-    build_index, diy_hints, find_text_in_file, get_absolute_path, get_all_symbols,
-    get_symbol_query, print_outline, recursion_dir, CodeHinter, CodeIndex,
+    build_index, build_pattern_automaton, diy_hints, find_definitions, find_multiline_in_file,
+    find_parse_errors, find_patterns_in_file, find_references, find_text_in_file,
+    get_absolute_path, get_all_symbols, get_identifier_at, get_imports, get_language,
+    get_symbol_query, open_in_editor, parse_location, parse_symbol_kinds, print_dir_outline,
+    print_outline, print_parse_errors, print_references, read_patterns_file, read_source,
+    recursion_dir, run_tui, support_symbol, CallDirection, CallGraph, CodeHinter, CodeIndex,
+    ColorChoice, IndexQuery, OutlineOptions, PatternLine, RankedIndex, SourceEncoding, SymbolKind,
+    SymbolMatch,
 };
 use colored::*;
 use indicatif::{ProgressBar, ProgressFinish, ProgressStyle};
@@ -10,11 +16,15 @@ use prettytable::{
     format::{self},
     row, Table,
 };
-use regex::Regex;
+use regex::{Regex, RegexBuilder};
 use rustyline::{error::ReadlineError, history::FileHistory, Editor};
-use std::{fs, path::Path, rc::Rc};
-
-mod lang;
+use std::{
+    collections::HashSet,
+    io::ErrorKind,
+    ops::Range,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
 #[derive(Default)]
 struct CommandArgs<'a> {
@@ -23,6 +33,15 @@ struct CommandArgs<'a> {
     language: &'a str,
     only_symbol: bool,
     use_reg: bool,
+    use_prefix: bool,
+    kinds: Vec<SymbolKind>,
+    color: ColorChoice,
+    /// 读取源码使用的编码
+    encoding: SourceEncoding,
+    show_parse_errors: bool,
+    multiline: bool,
+    /// -e 和 -f 指定的固定字符串模式
+    patterns: Vec<String>,
     start_interactive_mode: bool,
 }
 /**
@@ -35,21 +54,112 @@ fn main() {
         .about("a command code search engine")
         .version("0.0.3")
         .args(&[
-            arg!(-p --path <Path> "搜索路径，文件或目录").default_value("."),
-            arg!(-l --language <Language> "使用语言文件扩展名，如 rs、md等"),
+            arg!(-p --path <Path> "搜索路径，文件或目录")
+                .default_value(".")
+                .global(true),
+            arg!(-l --language <Language> "使用语言文件扩展名，如 rs、md等").global(true),
             arg!(-s --symbol "只搜索符号，如类名、函数名称等"),
             arg!(-k --key <Key> "关键字").requires_if("", "interactive"),
             arg!(-r --reg "启用正则表达式（会减缓搜索速度）"),
+            arg!(--prefix "符号按前缀匹配，需配合 -s 使用").conflicts_with("reg"),
+            arg!(--multiline "正则表达式在整个文件上匹配，可以跨行，需配合 -r 使用")
+                .requires("reg")
+                .conflicts_with("symbol"),
+            arg!(-e --pattern <Pattern> "固定字符串模式，可重复指定以同时搜索多个，结果中标出匹配的模式")
+                .action(ArgAction::Append)
+                .conflicts_with_all(["reg", "symbol"]),
+            arg!(-f --"pattern-file" <File> "模式文件，每行一个固定字符串，可与 -e、-k 同时使用")
+                .conflicts_with_all(["reg", "symbol"]),
+            arg!(--kind <Kind> "只搜索指定类型的符号，逗号分隔，如 fn,class"),
             arg!(-i --interactive "启用交互模式（该模式会构建索引，请指定具体的项目目录）"),
+            arg!(--tui "全屏交互模式，输入时实时更新结果，方向键选择并预览代码"),
+            arg!(--outline <Path> ... "打印文件或目录的大纲，可指定多个路径").num_args(1..),
+            arg!(--"body-size" "大纲中显示函数体、类体的行数"),
+            arg!(--"max-depth" <N> "大纲的最大层级，目录（含根目录）、文件和每层符号各算一层")
+                .value_parser(clap::value_parser!(usize)),
+            arg!(--"show-parse-errors" "列出存在语法错误（ERROR/MISSING 节点）的文件和位置，配合 -k 时在搜索结果后输出"),
+            arg!(--encoding <Encoding> "源码编码，如 gbk、shift_jis、utf-16le，auto 时只识别 BOM，默认为 UTF-8")
+                .default_value("auto")
+                .global(true),
+            arg!(--color <When> "颜色输出，auto 时遵循 NO_COLOR 环境变量")
+                .value_parser(["auto", "always", "never"])
+                .default_value("auto")
+                .global(true),
         ])
+        .subcommand(
+            Command::new("refs")
+                .about("查找符号的引用（不含定义处）")
+                .arg(arg!(<Name> "符号名称")),
+        )
+        .subcommand(
+            Command::new("def")
+                .about("跳转到光标处标识符的定义")
+                .arg(arg!(<Location> "光标位置，格式为 file:line:col")),
+        )
+        .subcommand(call_graph_command("callers", "查找调用该函数的函数"))
+        .subcommand(call_graph_command("callees", "查找该函数调用的函数"))
         .get_matches();
 
     let args = get_args(&matches);
+    args.color.apply();
+
+    if let Some(refs_matches) = matches.subcommand_matches("refs") {
+        let name = refs_matches.get_one::<String>("Name").unwrap();
+        do_find_references(Path::new(args.path), args.language, name, args.encoding);
+        return;
+    }
+
+    if let Some(def_matches) = matches.subcommand_matches("def") {
+        let location = def_matches.get_one::<String>("Location").unwrap();
+        do_goto_definition(Path::new(args.path), location, args.encoding);
+        return;
+    }
+
+    for (command, direction) in [
+        ("callers", CallDirection::Callers),
+        ("callees", CallDirection::Callees),
+    ] {
+        if let Some(call_matches) = matches.subcommand_matches(command) {
+            let name = call_matches.get_one::<String>("Name").unwrap();
+            let depth = *call_matches.get_one::<usize>("depth").unwrap();
+            let format = call_matches.get_one::<String>("format").unwrap();
+            let index = build_index(Path::new(args.path), args.encoding);
+            let call_graph =
+                build_call_graph(Path::new(args.path), args.language, &index, args.encoding);
+            match format.as_str() {
+                "dot" => println!("{}", call_graph.to_dot(name, direction, depth)),
+                "json" => println!("{}", call_graph.to_json(name, direction, depth)),
+                _ => call_graph.print_tree(name, direction, depth),
+            }
+            return;
+        }
+    }
+
+    if let Some(files) = matches.get_many::<String>("outline") {
+        let files: Vec<&str> = files.map(|file| file.as_str()).collect();
+        let options = OutlineOptions {
+            max_depth: matches.get_one::<usize>("max-depth").copied(),
+            kinds: args.kinds.clone(),
+            show_body_size: matches.get_flag("body-size"),
+        };
+        do_outline(&files, args.language, &options, args.encoding);
+        return;
+    }
 
     if args.start_interactive_mode {
         do_interactive_mode(&args);
         return;
     }
+
+    if matches.get_flag("tui") {
+        do_tui_mode(&args);
+        return;
+    }
+
+    if args.show_parse_errors && !matches.contains_id("key") {
+        do_show_parse_errors(Path::new(args.path), args.language, args.encoding);
+        return;
+    }
     // 扫描目录
     let path = Path::new(args.path);
     let mut pathes = vec![];
@@ -69,42 +179,132 @@ fn main() {
         .progress_chars("#>-"),
     );
 
-    let mut progress = 1;
     let mut reg: Option<Rc<Regex>> = None;
     if args.use_reg {
-        let regex = Regex::new(args.search_key).unwrap();
+        // 跨行匹配时 `^`、`$` 匹配每一行的开头和结尾
+        let regex = RegexBuilder::new(args.search_key)
+            .multi_line(args.multiline)
+            .build()
+            .unwrap_or_else(|err| {
+                eprintln!("{}", err.to_string().red());
+                std::process::exit(2);
+            });
         reg = Some(Rc::new(regex));
     }
-    for path in pathes {
+    let symbol_match = if args.use_reg {
+        SymbolMatch::Regex
+    } else if args.use_prefix {
+        SymbolMatch::Prefix
+    } else {
+        SymbolMatch::Literal
+    };
+    // 限定名称（如 `spec.containers[0].image`）在源码中不连续出现，只用最后一段预先过滤文件
+    let name_key = args
+        .search_key
+        .rsplit(['.', ':', ']', '>'])
+        .map(str::trim)
+        .find(|part| !part.is_empty())
+        .unwrap_or(args.search_key);
+    let automaton = if args.patterns.is_empty() {
+        None
+    } else {
+        Some(
+            build_pattern_automaton(&args.patterns).unwrap_or_else(|err| {
+                eprintln!("{}", err.red());
+                std::process::exit(2);
+            }),
+        )
+    };
+    let mut binary_files = 0;
+    for (progress, path) in (1..).zip(pathes) {
         // let path = doc.get_first(full_path_field).unwrap().as_str().unwrap();
         let path_str = path.to_str().unwrap();
         let mut result = vec![];
         let path_string = path_str.to_string();
         pb.set_position(progress);
         pb.set_message(path_string);
+        if let (Some(automaton), Some(_)) = (&automaton, path.extension()) {
+            match find_patterns_in_file(path_str, automaton, args.encoding) {
+                Ok(lines) => add_pattern_rows(&mut table, path_str, &args.patterns, lines),
+                Err(err) if err.kind() == ErrorKind::InvalidData => binary_files += 1,
+                Err(err) => pb.println(
+                    format!("Error read file {path_str}: {err}")
+                        .red()
+                        .to_string(),
+                ),
+            }
+            continue;
+        }
         if path.extension().is_some() {
             let path_extension = path.extension().unwrap().to_str().unwrap();
             // println!("search: {}", path_str.bright_black());
             if args.only_symbol {
-                let code = fs::read_to_string(Path::new(path_str)).unwrap_or("".to_string());
+                let code = read_source(path_str, args.encoding).unwrap_or("".to_string());
                 if (args.use_reg && reg.clone().unwrap().captures(code.as_str()).is_some())
-                    || code.contains(args.search_key)
+                    || code.contains(name_key)
                 {
-                    result =
-                        get_all_symbols(&code, args.search_key, get_symbol_query(path_extension));
+                    result = get_all_symbols(
+                        &code,
+                        args.search_key,
+                        symbol_match,
+                        get_symbol_query(path_extension),
+                    )
+                    .into_iter()
+                    .filter(|symbol| args.kinds.is_empty() || args.kinds.contains(&symbol.kind))
+                    .map(|symbol| {
+                        (
+                            symbol.line.to_string(),
+                            symbol.qualified_name,
+                            Some(symbol.kind),
+                        )
+                    })
+                    .collect();
                 }
             } else {
-                result = find_text_in_file(path_str, args.search_key, reg.clone()).expect(
-                    format!("Error read file {path_str}")
-                        .as_str()
-                        .red()
-                        .to_string()
-                        .as_str(),
-                );
+                let found = if args.multiline {
+                    // 跨行的匹配输出起止行号，如 `12-15`
+                    find_multiline_in_file(path_str, reg.as_ref().unwrap(), args.encoding).map(
+                        |matches| {
+                            matches
+                                .into_iter()
+                                .map(|found| {
+                                    let lines = if found.end_line > found.start_line {
+                                        format!("{}-{}", found.start_line, found.end_line)
+                                    } else {
+                                        found.start_line.to_string()
+                                    };
+                                    (lines, found.text)
+                                })
+                                .collect::<Vec<_>>()
+                        },
+                    )
+                } else {
+                    find_text_in_file(path_str, args.search_key, reg.clone(), args.encoding).map(
+                        |lines| {
+                            lines
+                                .into_iter()
+                                .map(|(line_number, line)| (line_number.to_string(), line))
+                                .collect()
+                        },
+                    )
+                };
+                match found {
+                    Ok(lines) => {
+                        result = lines
+                            .into_iter()
+                            .map(|(line_number, line)| (line_number, line, None))
+                            .collect();
+                    }
+                    Err(err) if err.kind() == ErrorKind::InvalidData => binary_files += 1,
+                    Err(err) => pb.println(
+                        format!("Error read file {path_str}: {err}")
+                            .red()
+                            .to_string(),
+                    ),
+                }
             }
         }
-        progress += 1;
-        for (line_number, line) in result {
+        for (line_number, line, kind) in result {
             let mut replace_str = args.search_key;
             if args.use_reg {
                 let re = reg.clone().unwrap();
@@ -114,22 +314,88 @@ fn main() {
             }
             let new_line =
                 line.replace(replace_str, replace_str.blue().bold().to_string().as_str());
-            table.add_row(row![
-                format!(
-                    "{}{}{}",
-                    path_str.green(),
-                    ":".green(),
-                    line_number.to_string().green()
-                ),
+            let location = format!("{}{}{}", path_str.green(), ":".green(), line_number.green());
+            if let Some(kind) = kind {
+                table.add_row(row![location, kind.as_str().purple(), new_line.trim()]);
+            } else {
                 // line_number.to_string().normal().bold(),
-                new_line.trim()
-            ]);
+                table.add_row(row![location, new_line.trim()]);
+            }
         }
     }
     pb.with_finish(ProgressFinish::AndClear);
-    println!("");
+    println!();
     // 输出结果
     table.printstd();
+    if binary_files > 0 {
+        println!(
+            "{}",
+            format!("已跳过 {binary_files} 个二进制文件").bright_black()
+        );
+    }
+    if args.show_parse_errors {
+        println!();
+        do_show_parse_errors(path, args.language, args.encoding);
+    }
+}
+
+/**
+* 高亮文本中的字节范围，先合并重叠的位置，避免替换到颜色转义字符
+*/
+fn highlight_ranges(text: &str, ranges: &[Range<usize>]) -> String {
+    let mut ranges: Vec<(usize, usize)> = ranges
+        .iter()
+        .map(|range| (range.start, range.end))
+        .collect();
+    ranges.sort();
+    let mut highlighted = String::new();
+    let mut last = 0;
+    for (start, end) in ranges {
+        if end <= last {
+            continue;
+        }
+        let start = start.max(last);
+        highlighted.push_str(&text[last..start]);
+        highlighted.push_str(&text[start..end].blue().bold().to_string());
+        last = end;
+    }
+    highlighted.push_str(&text[last..]);
+    highlighted
+}
+
+/**
+* 输出多模式搜索的结果，第二列为该行匹配到的模式
+*/
+fn add_pattern_rows(
+    table: &mut Table,
+    path_str: &str,
+    patterns: &[String],
+    lines: Vec<PatternLine>,
+) {
+    for line in lines {
+        let ranges: Vec<Range<usize>> = line
+            .matches
+            .iter()
+            .map(|(_, range)| range.clone())
+            .collect();
+        let highlighted = highlight_ranges(&line.text, &ranges);
+        let matched_patterns: Vec<&str> = line
+            .patterns()
+            .into_iter()
+            .map(|pattern| patterns[pattern].as_str())
+            .collect();
+        let location = format!(
+            "{}{}{}",
+            path_str.green(),
+            ":".green(),
+            line.line.to_string().green()
+        );
+        table.add_row(row![
+            location,
+            matched_patterns.join(", ").purple(),
+            highlighted.trim()
+        ]);
+    }
 }
 
 /**
@@ -161,26 +427,243 @@ fn get_args<'a>(matches: &'a ArgMatches) -> CommandArgs<'a> {
         args.use_reg = false;
     }
 
+    if let Some(use_prefix) = matches.get_one::<bool>("prefix") {
+        args.use_prefix = *use_prefix;
+    } else {
+        args.use_prefix = false;
+    }
+
+    if let Some(kinds) = matches.get_one::<String>("kind") {
+        args.kinds = parse_symbol_kinds(kinds).unwrap_or_else(|err| {
+            eprintln!("{}", err.red());
+            std::process::exit(2);
+        });
+    }
+
+    if let Some(color) = matches.get_one::<String>("color") {
+        args.color = color.parse().unwrap_or_default();
+    }
+
+    if let Some(encoding) = matches.get_one::<String>("encoding") {
+        args.encoding = encoding.parse().unwrap_or_else(|err: String| {
+            eprintln!("{}", err.red());
+            std::process::exit(2);
+        });
+    }
+
+    args.show_parse_errors = matches.get_flag("show-parse-errors");
+    args.multiline = matches.get_flag("multiline");
+
+    if let Some(patterns) = matches.get_many::<String>("pattern") {
+        args.patterns.extend(patterns.cloned());
+    }
+    if let Some(pattern_file) = matches.get_one::<String>("pattern-file") {
+        match read_patterns_file(Path::new(pattern_file), args.encoding) {
+            Ok(patterns) => args.patterns.extend(patterns),
+            Err(err) => {
+                eprintln!(
+                    "{}",
+                    format!("无法读取模式文件 {pattern_file}: {err}").red()
+                );
+                std::process::exit(2);
+            }
+        }
+    }
+    // 同时指定 -k 时，关键字作为第一个模式一起搜索
+    if !args.patterns.is_empty() && !args.search_key.is_empty() {
+        args.patterns.insert(0, args.search_key.to_string());
+    }
+
     if let Some(interactive_mode) = matches.get_one::<bool>("interactive") {
         args.start_interactive_mode = *interactive_mode;
     } else {
         args.start_interactive_mode = false;
     }
-    return args;
+    args
+}
+/**
+* 依次打印文件或目录的大纲，多个文件时先输出文件路径
+*/
+fn do_outline(files: &[&str], language: &str, options: &OutlineOptions, encoding: SourceEncoding) {
+    for file in files {
+        let outline_path = Path::new(file);
+        if outline_path.is_dir() {
+            print_dir_outline(outline_path, language, options, encoding);
+            continue;
+        }
+        let Some(path_extension) = outline_path.extension().and_then(|ext| ext.to_str()) else {
+            println!("{}", format!("文件路径不存在: {file}").red());
+            continue;
+        };
+        if !outline_path.is_file() {
+            println!("{}", format!("文件路径不存在: {file}").red());
+            continue;
+        }
+        if !support_symbol(path_extension) {
+            println!("{}", format!("不支持该文件类型的大纲: {file}").red());
+            continue;
+        }
+        if files.len() > 1 {
+            println!("{}", file.green());
+        }
+        let code = read_source(outline_path, encoding).unwrap_or_default();
+        print_outline(&code, get_symbol_query(path_extension).as_ref(), options);
+    }
+}
+
+/**
+* 列出目录下存在语法错误的文件，这些文件仍会尽量提取符号
+*/
+fn do_show_parse_errors(root_path: &Path, language: &str, encoding: SourceEncoding) {
+    let mut pathes = vec![];
+    recursion_dir(root_path, &mut pathes, language);
+    let mut error_files = 0;
+    for path in pathes {
+        let Some(path_extension) = path.extension().and_then(|ext| ext.to_str()) else {
+            continue;
+        };
+        if !support_symbol(path_extension) {
+            continue;
+        }
+        let code = read_source(&path, encoding).unwrap_or_default();
+        let errors = find_parse_errors(&code, get_symbol_query(path_extension).as_ref());
+        if !errors.is_empty() {
+            error_files += 1;
+            print_parse_errors(path.to_str().unwrap(), &errors);
+        }
+    }
+    if error_files == 0 {
+        println!("{}", "未发现语法错误".green());
+    }
+}
+
+/**
+* 查找目录下所有文件中的引用，按文件分组输出
+*/
+fn do_find_references(root_path: &Path, language: &str, name: &str, encoding: SourceEncoding) {
+    let mut pathes = vec![];
+    recursion_dir(root_path, &mut pathes, language);
+    for path in pathes {
+        let path_extension = path.extension().unwrap().to_str().unwrap();
+        if !support_symbol(path_extension) {
+            continue;
+        }
+        let code = read_source(&path, encoding).unwrap_or("".to_string());
+        if !code.contains(name) {
+            continue;
+        }
+        let references = find_references(&code, name, get_symbol_query(path_extension).as_ref());
+        if !references.is_empty() {
+            print_references(path.to_str().unwrap(), &references);
+        }
+    }
+}
+
+/**
+* 解析光标处的标识符，在索引中查找其定义
+*/
+fn do_goto_definition(root_path: &Path, location: &str, encoding: SourceEncoding) {
+    let Some(mut location) = parse_location(location) else {
+        eprintln!("{}", "位置格式应为 file:line:col".red());
+        std::process::exit(2);
+    };
+    let source_path = Path::new(&location.path);
+    let path_extension = source_path.extension().and_then(|ext| ext.to_str());
+    let code = read_source(source_path, encoding);
+    let (Some(path_extension), Ok(code)) = (path_extension, code) else {
+        eprintln!("{}", "文件路径不存在".red());
+        std::process::exit(2);
+    };
+    let symbol_query = get_symbol_query(path_extension);
+    let Some(name) =
+        get_identifier_at(&code, location.line, location.column, symbol_query.as_ref())
+    else {
+        eprintln!("{}", "光标处没有标识符".red());
+        std::process::exit(1);
+    };
+    let imports = get_imports(&code, symbol_query.as_ref());
+    location.path = get_absolute_path(source_path);
+
+    let index = build_index(root_path, encoding);
+    let definitions = find_definitions(&index, &name, &location, &imports);
+    if definitions.is_empty() {
+        eprintln!("{}", format!("未找到 {name} 的定义").red());
+        std::process::exit(1);
+    }
+    for item in definitions {
+        println!(
+            "{}:{} {} {}",
+            item.path,
+            item.line,
+            item.kind.as_str().purple(),
+            item.qualified_name
+        );
+    }
+}
+
+/**
+* callers/callees 子命令
+*/
+fn call_graph_command(name: &'static str, about: &'static str) -> Command {
+    Command::new(name).about(about).args(&[
+        arg!(<Name> "函数名称，可以是限定名称"),
+        arg!(-d --depth <Depth> "遍历深度")
+            .value_parser(clap::value_parser!(usize))
+            .default_value("3"),
+        arg!(-f --format <Format> "输出格式")
+            .value_parser(["tree", "dot", "json"])
+            .default_value("tree"),
+    ])
+}
+
+/**
+* 基于符号索引构建调用图
+*/
+fn build_call_graph(
+    root_path: &Path,
+    language: &str,
+    index: &[CodeIndex],
+    encoding: SourceEncoding,
+) -> CallGraph {
+    let mut pathes = vec![];
+    recursion_dir(root_path, &mut pathes, language);
+    let defined: HashSet<(&str, &str)> = index
+        .iter()
+        .filter(|item| matches!(item.kind, SymbolKind::Function | SymbolKind::Method))
+        .map(|item| (get_language(&item.path), item.line_code.as_str()))
+        .collect();
+    CallGraph::build(&pathes, &defined, encoding)
+}
+
+/**
+* 全屏交互模式，回车在编辑器中打开选中的结果
+*/
+fn do_tui_mode(args: &CommandArgs) {
+    let path = Path::new(args.path);
+    let index = build_index(path, args.encoding);
+    let root_path = PathBuf::from(get_absolute_path(path));
+    if let Err(err) = run_tui(&index, &root_path, &args.kinds, args.encoding) {
+        eprintln!("{}", format!("Error enter tui mode: {err}").red());
+        std::process::exit(1);
+    }
 }
+
 /**
 * 处理交互模式
 */
 fn do_interactive_mode(args: &CommandArgs) {
     let path = Path::new(args.path);
     // 构建索引
-    let index = build_index(&path);
+    let index = build_index(path, args.encoding);
     // 开始读取指令
     let h = CodeHinter { hints: diy_hints() };
     let mut rl: Editor<CodeHinter, FileHistory> =
         Editor::new().expect("Error enter interactive mode");
     rl.set_helper(Some(h));
-    println!("当前根路径为 : {}", get_absolute_path(path).green());
+    let root_path = PathBuf::from(get_absolute_path(path));
+    println!("当前根路径为 : {}", root_path.display().to_string().green());
+    // 上一次查询的结果，供 open 命令按序号打开
+    let mut last_result: Vec<&CodeIndex> = vec![];
     loop {
         let readline = rl.readline(">> ".green().to_string().as_str());
         match readline {
@@ -190,36 +673,90 @@ fn do_interactive_mode(args: &CommandArgs) {
                     println!("退出交互模式");
                     break;
                 } else if line.starts_with("outline") {
-                    let outline_args: Vec<&str> = line.split(" ").collect();
-                    if outline_args.len() == 2 {
-                        let outline_path = Path::new(outline_args[1]);
-                        if outline_path.exists() && outline_path.extension().is_some() {
-                            let path_extension =
-                                outline_path.extension().unwrap().to_str().unwrap();
-                            let code = fs::read_to_string(outline_path).unwrap();
-                            print_outline(&code, get_symbol_query(path_extension));
-                        } else {
-                            println!("{}", "文件路径不存在".red());
-                        }
+                    let outline_args: Vec<&str> = line.split_whitespace().skip(1).collect();
+                    if outline_args.is_empty() {
+                        println!("{}", "参数非法".red());
                     } else {
+                        let options = OutlineOptions {
+                            kinds: args.kinds.clone(),
+                            show_body_size: true,
+                            ..Default::default()
+                        };
+                        do_outline(&outline_args, args.language, &options, args.encoding);
+                    }
+                } else if let Some(name) = line.strip_prefix("refs ") {
+                    let name = name.trim();
+                    if name.is_empty() {
                         println!("{}", "参数非法".red());
+                    } else {
+                        do_find_references(path, args.language, name, args.encoding);
                     }
+                } else if line.starts_with("callers ") || line.starts_with("callees ") {
+                    let (command, name) = line.split_once(' ').unwrap();
+                    let direction = if command == "callers" {
+                        CallDirection::Callers
+                    } else {
+                        CallDirection::Callees
+                    };
+                    let call_graph = build_call_graph(path, args.language, &index, args.encoding);
+                    call_graph.print_tree(name.trim(), direction, 3);
                 } else if line.trim_end() == "help" {
                     // 打印帮助信息
-                } else if line.trim().len() == 0 {
+                } else if line.trim().is_empty() {
                     println!("{}", "关键词不能为空".red());
+                } else if let Some(number) = line
+                    .strip_prefix("open ")
+                    .and_then(|number| number.trim().parse::<usize>().ok())
+                {
+                    match number.checked_sub(1).and_then(|i| last_result.get(i)) {
+                        Some(item) => {
+                            if let Err(err) = open_in_editor(&item.path, item.line) {
+                                println!("{}", err.to_string().red());
+                            }
+                        }
+                        None if last_result.is_empty() => {
+                            println!("{}", "没有可打开的结果，请先查询".red())
+                        }
+                        None => println!(
+                            "{}",
+                            format!("序号超出范围，应为 1-{}", last_result.len()).red()
+                        ),
+                    }
                 } else {
-                    // 查询信息
+                    // 查询信息，支持 AND/OR/NOT、引号和 kind:/lang:/path:/name:/doc: 限定
+                    let query = match IndexQuery::parse(&line) {
+                        Ok(query) => query,
+                        Err(err) => {
+                            println!("{}", err.red());
+                            continue;
+                        }
+                    };
                     let result: Vec<&CodeIndex> = index
                         .iter()
-                        .filter(|item| item.line_code.contains(line.as_str()))
+                        .filter(|item| args.kinds.is_empty() || args.kinds.contains(&item.kind))
+                        .filter(|item| query.matches(item))
                         .collect();
-                    for item in result {
-                        let replace_str = line.clone();
-                        let new_line = item
-                            .line_code
-                            .replace(&replace_str, replace_str.blue().bold().to_string().as_str());
-                        println!("{}({}:{})", new_line, item.path, item.line);
+                    last_result.clear();
+                    for RankedIndex {
+                        item, highlights, ..
+                    } in query.rank(result, &root_path)
+                    {
+                        last_result.push(item);
+                        let new_line = highlight_ranges(&item.qualified_name, &highlights);
+                        println!(
+                            "{} {} {}({}:{})",
+                            format!("[{}]", last_result.len()).bright_black(),
+                            item.kind.as_str().purple(),
+                            new_line,
+                            item.path,
+                            item.line
+                        );
+                        println!("    {}", item.signature.cyan());
+                        if let Some(doc) = &item.doc {
+                            for doc_line in doc.lines() {
+                                println!("    {}", doc_line.bright_black());
+                            }
+                        }
                     }
                 }
             }
@@ -238,25 +775,3 @@ fn do_interactive_mode(args: &CommandArgs) {
         }
     }
 }
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_outline() {
-        let line = "outline /Volumes/T7/Github/code-search/examples/main.cpp";
-        let outline_args: Vec<&str> = line.split(" ").collect();
-        if outline_args.len() == 2 {
-            let outline_path = Path::new(outline_args[1]);
-            if outline_path.exists() && outline_path.extension().is_some() {
-                let path_extension = outline_path.extension().unwrap().to_str().unwrap();
-                let code = fs::read_to_string(outline_path).unwrap();
-                print_outline(&code, get_symbol_query(path_extension));
-            } else {
-                println!("{}", "文件路径不存在".red());
-            }
-        } else {
-            println!("{}", "参数非法".red());
-        }
-    }
-}
@@ -0,0 +1,471 @@
+use regex::Regex;
+use std::{ops::Range, path::Path};
+
+use crate::{fuzzy_match, parse_symbol_kinds, CodeIndex, SymbolKind};
+
+/**
+* 交互模式的查询表达式，如 `kind:fn lang:go path:src/ name:parse* NOT test`
+*/
+#[derive(Debug)]
+pub enum IndexQuery {
+    Term(Term),
+    Not(Box<IndexQuery>),
+    And(Vec<IndexQuery>),
+    Or(Vec<IndexQuery>),
+}
+
+/**
+* 查询中的单个条件
+*/
+#[derive(Debug)]
+pub enum Term {
+    /// 限定名称包含该文本
+    Text(String),
+    /// kind:fn,class
+    Kind(Vec<SymbolKind>),
+    /// lang:go，按文件扩展名匹配
+    Lang(Regex),
+    /// path:src/，不含通配符时按包含匹配
+    Path(Regex),
+    /// name:parse*，含 `.` 或 `:` 时匹配限定名称
+    Name { regex: Regex, qualified: bool },
+    /// doc:关键字，忽略大小写
+    Doc(String),
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    /// 字段限定，如 `name:"foo bar"`
+    Field(String, String),
+    Phrase(String),
+    And,
+    Or,
+    Not,
+    LeftParen,
+    RightParen,
+}
+
+/**
+* 排序后的查询结果
+*/
+#[derive(Debug)]
+pub struct RankedIndex<'a> {
+    pub item: &'a CodeIndex,
+    pub score: i64,
+    /// 限定名称中需要高亮的字节范围
+    pub highlights: Vec<Range<usize>>,
+}
+
+impl IndexQuery {
+    /**
+     * 解析查询，相邻的条件默认为 AND，优先级 NOT > AND > OR
+     */
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Err("关键词不能为空".to_string());
+        }
+        let mut parser = QueryParser { tokens, pos: 0 };
+        let query = parser.parse_or()?;
+        match parser.tokens.get(parser.pos) {
+            Some(Token::RightParen) => Err("多余的右括号".to_string()),
+            Some(_) => Err("无法解析的查询".to_string()),
+            None => Ok(query),
+        }
+    }
+
+    pub fn matches(&self, item: &CodeIndex) -> bool {
+        match self {
+            IndexQuery::Term(term) => term.matches(item),
+            IndexQuery::Not(query) => !query.matches(item),
+            IndexQuery::And(queries) => queries.iter().all(|query| query.matches(item)),
+            IndexQuery::Or(queries) => queries.iter().any(|query| query.matches(item)),
+        }
+    }
+
+    /**
+     * 按匹配质量、名称完全相同、符号类型和路径深度排序，分数相同时保持索引顺序
+     */
+    pub fn rank<'a>(&self, items: Vec<&'a CodeIndex>, root: &Path) -> Vec<RankedIndex<'a>> {
+        let text_terms = self.text_terms();
+        let mut ranked: Vec<RankedIndex> = items
+            .into_iter()
+            .map(|item| {
+                let mut score = kind_score(item.kind) - path_depth(&item.path, root) * 2;
+                let mut highlights = vec![];
+                for term in &text_terms {
+                    score += score_text(term, item, &mut highlights).unwrap_or(0);
+                }
+                RankedIndex {
+                    item,
+                    score,
+                    highlights,
+                }
+            })
+            .collect();
+        ranked.sort_by_key(|ranked| std::cmp::Reverse(ranked.score));
+        ranked
+    }
+
+    /**
+     * 非否定的文本条件，用于高亮结果
+     */
+    pub fn text_terms(&self) -> Vec<&str> {
+        match self {
+            IndexQuery::Term(Term::Text(text)) => vec![text.as_str()],
+            IndexQuery::Term(_) | IndexQuery::Not(_) => vec![],
+            IndexQuery::And(queries) | IndexQuery::Or(queries) => {
+                queries.iter().flat_map(IndexQuery::text_terms).collect()
+            }
+        }
+    }
+}
+
+impl Term {
+    fn parse(field: &str, value: &str) -> Result<Self, String> {
+        match field {
+            "kind" => Ok(Term::Kind(parse_symbol_kinds(value)?)),
+            "lang" => Ok(Term::Lang(glob_to_regex(&value.to_lowercase(), true)?)),
+            "path" => Ok(Term::Path(glob_to_regex(value, false)?)),
+            "name" => Ok(Term::Name {
+                regex: glob_to_regex(value, true)?,
+                qualified: value.contains(['.', ':']),
+            }),
+            "doc" => Ok(Term::Doc(value.to_lowercase())),
+            other => Err(format!("未知的字段: {other}")),
+        }
+    }
+
+    fn matches(&self, item: &CodeIndex) -> bool {
+        match self {
+            Term::Text(text) => {
+                item.qualified_name.contains(text.as_str())
+                    || fuzzy_match(text, &item.line_code).is_some()
+            }
+            Term::Kind(kinds) => kinds.contains(&item.kind),
+            Term::Lang(regex) => Path::new(&item.path)
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| regex.is_match(&extension.to_lowercase())),
+            Term::Path(regex) => regex.is_match(&item.path),
+            Term::Name { regex, qualified } => {
+                if *qualified {
+                    regex.is_match(&item.qualified_name)
+                } else {
+                    regex.is_match(&item.line_code)
+                }
+            }
+            Term::Doc(doc_key) => item
+                .doc
+                .as_ref()
+                .is_some_and(|doc| doc.to_lowercase().contains(doc_key.as_str())),
+        }
+    }
+}
+
+/**
+* 文本条件的得分，并记录高亮范围：名称完全相同 > 名称前缀 > 限定名称包含 > 名称模糊匹配
+*/
+fn score_text(term: &str, item: &CodeIndex, highlights: &mut Vec<Range<usize>>) -> Option<i64> {
+    let name = item.line_code.as_str();
+    // 名称通常是限定名称的末尾部分
+    let name_offset = item
+        .qualified_name
+        .strip_suffix(name)
+        .map_or(0, |prefix| prefix.len());
+    if name == term || name.eq_ignore_ascii_case(term) {
+        highlights.push(name_offset..name_offset + name.len());
+        return Some(if name == term { 1100 } else { 1000 });
+    }
+    if name.starts_with(term) {
+        highlights.push(name_offset..name_offset + term.len());
+        return Some(800);
+    }
+    if let Some(start) = item.qualified_name.find(term) {
+        highlights.push(start..start + term.len());
+        // 匹配的位置越靠后（越接近名称本身）越好
+        return Some(500 + (start * 10 / item.qualified_name.len().max(1)) as i64);
+    }
+    let fuzzy = fuzzy_match(term, name)?;
+    highlights.extend(fuzzy.positions.iter().map(|&position| {
+        let len = name[position..].chars().next().map_or(1, char::len_utf8);
+        name_offset + position..name_offset + position + len
+    }));
+    Some(fuzzy.score)
+}
+
+/**
+* 类型和函数优先于字段、常量，再优先于标题和配置键
+*/
+fn kind_score(kind: SymbolKind) -> i64 {
+    match kind {
+        SymbolKind::Class
+        | SymbolKind::Struct
+        | SymbolKind::Interface
+        | SymbolKind::Enum
+        | SymbolKind::Type => 40,
+        SymbolKind::Function | SymbolKind::Method => 35,
+        SymbolKind::Module | SymbolKind::Macro => 25,
+        SymbolKind::Constant | SymbolKind::Field | SymbolKind::Variant => 15,
+        SymbolKind::Heading | SymbolKind::Key => 0,
+    }
+}
+
+/**
+* 文件相对于根路径的层级，越浅越靠前
+*/
+fn path_depth(path: &str, root: &Path) -> i64 {
+    let path = Path::new(path);
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .count()
+        .min(20) as i64
+}
+
+/**
+* 通配符转换为正则表达式，`*` 匹配任意字符，`?` 匹配单个字符
+* 不含通配符且不要求完整匹配时按包含匹配，否则匹配到末尾
+*/
+fn glob_to_regex(glob: &str, full_match: bool) -> Result<Regex, String> {
+    if glob.is_empty() {
+        return Err("字段的值不能为空".to_string());
+    }
+    let mut pattern = String::new();
+    for ch in glob.chars() {
+        match ch {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            _ => pattern.push_str(&regex::escape(&ch.to_string())),
+        }
+    }
+    let has_wildcard = glob.contains(['*', '?']);
+    if full_match {
+        pattern = format!("^{pattern}$");
+    } else if has_wildcard {
+        pattern.push('$');
+    }
+    Regex::new(&pattern).map_err(|err| err.to_string())
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+    while let Some(&ch) = chars.peek() {
+        match ch {
+            _ if ch.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LeftParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RightParen);
+            }
+            '"' => {
+                chars.next();
+                tokens.push(Token::Phrase(read_quoted(&mut chars)?));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_whitespace() || ch == '(' || ch == ')' {
+                        break;
+                    }
+                    chars.next();
+                    // 字段的值可以用引号包含空格，如 name:"foo bar"
+                    if ch == '"' && word.ends_with(':') {
+                        let field = word.trim_end_matches(':').to_string();
+                        tokens.push(Token::Field(field, read_quoted(&mut chars)?));
+                        word.clear();
+                        break;
+                    }
+                    word.push(ch);
+                }
+                if word.is_empty() {
+                    continue;
+                }
+                tokens.push(match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => match word.split_once(':') {
+                        Some((field, value)) if is_field(field) && !value.is_empty() => {
+                            Token::Field(field.to_string(), value.to_string())
+                        }
+                        _ => Token::Word(word),
+                    },
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/**
+* 只有已知的字段名才作为限定，`Foo::bar` 之类的限定名称按文本处理
+*/
+fn is_field(field: &str) -> bool {
+    matches!(field, "kind" | "lang" | "path" | "name" | "doc")
+}
+
+fn read_quoted(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<String, String> {
+    let mut phrase = String::new();
+    for ch in chars.by_ref() {
+        if ch == '"' {
+            return Ok(phrase);
+        }
+        phrase.push(ch);
+    }
+    Err("引号未闭合".to_string())
+}
+
+struct QueryParser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl QueryParser {
+    fn parse_or(&mut self) -> Result<IndexQuery, String> {
+        let mut queries = vec![self.parse_and()?];
+        while self.tokens.get(self.pos) == Some(&Token::Or) {
+            self.pos += 1;
+            queries.push(self.parse_and()?);
+        }
+        Ok(flatten(queries, IndexQuery::Or))
+    }
+
+    fn parse_and(&mut self) -> Result<IndexQuery, String> {
+        let mut queries = vec![self.parse_not()?];
+        loop {
+            match self.tokens.get(self.pos) {
+                Some(Token::And) => {
+                    self.pos += 1;
+                    queries.push(self.parse_not()?);
+                }
+                None | Some(Token::Or) | Some(Token::RightParen) => break,
+                Some(_) => queries.push(self.parse_not()?),
+            }
+        }
+        Ok(flatten(queries, IndexQuery::And))
+    }
+
+    fn parse_not(&mut self) -> Result<IndexQuery, String> {
+        if self.tokens.get(self.pos) == Some(&Token::Not) {
+            self.pos += 1;
+            return Ok(IndexQuery::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<IndexQuery, String> {
+        let Some(token) = self.tokens.get(self.pos) else {
+            return Err("查询不完整，缺少条件".to_string());
+        };
+        self.pos += 1;
+        match token {
+            Token::Word(text) | Token::Phrase(text) => {
+                Ok(IndexQuery::Term(Term::Text(text.clone())))
+            }
+            Token::Field(field, value) => Ok(IndexQuery::Term(Term::parse(field, value)?)),
+            Token::LeftParen => {
+                let query = self.parse_or()?;
+                if self.tokens.get(self.pos) != Some(&Token::RightParen) {
+                    return Err("缺少右括号".to_string());
+                }
+                self.pos += 1;
+                Ok(query)
+            }
+            Token::RightParen => Err("多余的右括号".to_string()),
+            Token::And | Token::Or | Token::Not => Err("运算符缺少条件".to_string()),
+        }
+    }
+}
+
+fn flatten(mut queries: Vec<IndexQuery>, combine: fn(Vec<IndexQuery>) -> IndexQuery) -> IndexQuery {
+    if queries.len() == 1 {
+        queries.pop().unwrap()
+    } else {
+        combine(queries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::code_index as index;
+
+    #[test]
+    fn test_index_query() {
+        let items = [
+            index("/p/src/lib.rs", "parse_code", SymbolKind::Function),
+            CodeIndex {
+                doc: Some("Parses the source".to_string()),
+                ..index("/p/src/parser.go", "Parser.parseFile", SymbolKind::Method)
+            },
+            index("/p/tests/parse_test.go", "TestParse", SymbolKind::Function),
+            index("/p/src/lib.rs", "Code Search", SymbolKind::Heading),
+        ];
+        let matched = |query: &str| -> Vec<&str> {
+            let query = IndexQuery::parse(query).unwrap();
+            items
+                .iter()
+                .filter(|item| query.matches(item))
+                .map(|item| item.qualified_name.as_str())
+                .collect()
+        };
+        assert_eq!(matched("kind:fn lang:go"), vec!["TestParse"]);
+        assert_eq!(
+            matched("name:parse* OR Test"),
+            vec!["parse_code", "Parser.parseFile", "TestParse"]
+        );
+        assert_eq!(
+            matched("path:src/ NOT (kind:heading OR lang:rs)"),
+            vec!["Parser.parseFile"]
+        );
+        assert_eq!(
+            matched("name:Parser.* AND doc:SOURCE"),
+            vec!["Parser.parseFile"]
+        );
+        assert_eq!(matched(r#""Code Search""#), vec!["Code Search"]);
+        assert_eq!(matched("path:*_test.go"), vec!["TestParse"]);
+        assert_eq!(
+            IndexQuery::parse("parse NOT code").unwrap().text_terms(),
+            vec!["parse"]
+        );
+        assert!(IndexQuery::parse("(kind:fn").is_err());
+        assert!(IndexQuery::parse("name:\"parse").is_err());
+        assert!(IndexQuery::parse("kind:unknown").is_err());
+        assert!(IndexQuery::parse("parse OR").is_err());
+    }
+
+    #[test]
+    fn test_rank_index() {
+        let items = [
+            index(
+                "/p/src/deep/mod/symbols.rs",
+                "get_all_symbols",
+                SymbolKind::Function,
+            ),
+            index("/p/README.md", "gas", SymbolKind::Heading),
+            index("/p/src/lib.rs", "getAllSymbols", SymbolKind::Method),
+            index("/p/src/lib.rs", "go_away_soon", SymbolKind::Field),
+        ];
+        let query = IndexQuery::parse("gas").unwrap();
+        let result: Vec<&CodeIndex> = items.iter().filter(|item| query.matches(item)).collect();
+        let ranked: Vec<&str> = query
+            .rank(result, Path::new("/p"))
+            .iter()
+            .map(|ranked| ranked.item.qualified_name.as_str())
+            .collect();
+        assert_eq!(
+            ranked,
+            vec!["gas", "getAllSymbols", "get_all_symbols", "go_away_soon"]
+        );
+        let ranked = query.rank(vec![&items[2]], Path::new("/p"));
+        assert_eq!(ranked[0].highlights, vec![0..1, 3..4, 6..7]);
+    }
+}
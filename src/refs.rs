@@ -0,0 +1,155 @@
+use colored::Colorize;
+use std::collections::HashSet;
+use tree_sitter::{Node, QueryCursor};
+
+use crate::{get_compiled_queries, get_qualified_name, lang::SymbolQuery, parse_code};
+
+/**
+* 符号的一次引用（非定义处的标识符）
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub line: usize,
+    pub column: usize,
+    /// 引用所在的符号，如所在的函数、类
+    pub enclosing: Option<String>,
+    pub line_code: String,
+}
+
+/**
+* 查找源码中名称为 name 的标识符，排除符号定义处
+*/
+pub fn find_references(code: &str, name: &str, symbol_query: &dyn SymbolQuery) -> Vec<Reference> {
+    let Some(tree) = parse_code(code, symbol_query) else {
+        return vec![];
+    };
+    let definitions = get_definition_nodes(code, name, &tree.root_node(), symbol_query);
+    let identifier_kinds = symbol_query.get_identifier_kinds();
+    let lines: Vec<&str> = code.lines().collect();
+
+    let mut references = vec![];
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if identifier_kinds.contains(&node.kind())
+            && &code[node.byte_range()] == name
+            && !definitions.contains(&node.id())
+            && !is_key_node_name(&node, symbol_query)
+        {
+            let position = node.start_position();
+            let line_code = lines.get(position.row).unwrap_or(&"");
+            references.push(Reference {
+                line: position.row + 1,
+                // 按字符计算列号，与源码的原始编码无关，也与 `def` 的 file:line:col 一致
+                column: line_code
+                    .get(..position.column)
+                    .map_or(position.column, |prefix| prefix.chars().count())
+                    + 1,
+                enclosing: get_enclosing_symbol(code, &node, symbol_query),
+                line_code: line_code.trim().to_string(),
+            });
+        }
+        // 逆序入栈，保证按源码顺序遍历
+        let children: Vec<Node> = node.children(&mut node.walk()).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    references
+}
+
+/**
+* 通过符号查询获取定义处的名称节点
+*/
+fn get_definition_nodes(
+    code: &str,
+    name: &str,
+    root_node: &Node,
+    symbol_query: &dyn SymbolQuery,
+) -> HashSet<usize> {
+    let mut query_cursor = QueryCursor::new();
+    let mut definitions = HashSet::new();
+    for query in get_compiled_queries(symbol_query).iter() {
+        for (m, capture_index) in query_cursor.captures(query, *root_node, code.as_bytes()) {
+            let node = m.captures[capture_index].node;
+            if &code[node.byte_range()] == name {
+                definitions.insert(node.id());
+            }
+        }
+    }
+    definitions
+}
+
+/**
+* 节点是否为大纲节点（如接口方法、字段声明）的名称
+*/
+fn is_key_node_name(node: &Node, symbol_query: &dyn SymbolQuery) -> bool {
+    node.parent().is_some_and(|parent| {
+        symbol_query.is_key_node(&parent) && symbol_query.get_symbol_name(&parent) == Some(*node)
+    })
+}
+
+/**
+* 获取节点所在的最内层符号的限定名称
+*/
+pub(crate) fn get_enclosing_symbol(
+    code: &str,
+    node: &Node,
+    symbol_query: &dyn SymbolQuery,
+) -> Option<String> {
+    let mut parent = node.parent();
+    while let Some(current) = parent {
+        if symbol_query.is_key_node(&current) {
+            if let Some(name_node) = symbol_query.get_symbol_name(&current) {
+                return Some(get_qualified_name(code, &name_node, symbol_query));
+            }
+        }
+        parent = current.parent();
+    }
+    None
+}
+
+/**
+* 按所在符号分组打印文件中的引用
+*/
+pub fn print_references(path: &str, references: &[Reference]) {
+    println!("{}", path.green());
+    let mut groups: Vec<(&Option<String>, Vec<&Reference>)> = vec![];
+    for reference in references {
+        match groups
+            .iter_mut()
+            .find(|(enclosing, _)| **enclosing == reference.enclosing)
+        {
+            Some((_, group)) => group.push(reference),
+            None => groups.push((&reference.enclosing, vec![reference])),
+        }
+    }
+    for (enclosing, group) in groups {
+        let name = enclosing.as_deref().unwrap_or("<top level>");
+        println!("  {}", name.purple());
+        for reference in group {
+            println!(
+                "    {}{}{} {}",
+                reference.line.to_string().green(),
+                ":".green(),
+                reference.column.to_string().green(),
+                reference.line_code
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lang::RustQuery;
+
+    #[test]
+    fn test_find_references() {
+        let code =
+            "struct Shape;\nfn area(shape: Shape) {}\nfn main() {\n    let shape = Shape;\n}\n";
+        let references = find_references(code, "Shape", &RustQuery);
+        let lines: Vec<(usize, Option<&str>)> = references
+            .iter()
+            .map(|reference| (reference.line, reference.enclosing.as_deref()))
+            .collect();
+        assert_eq!(lines, vec![(2, Some("area")), (4, Some("main"))]);
+    }
+}
@@ -0,0 +1,472 @@
+use ratatui::{
+    crossterm::{
+        event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+        execute,
+        terminal::{enable_raw_mode, EnterAlternateScreen},
+    },
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, ListState, Paragraph},
+    DefaultTerminal, Frame,
+};
+use std::{io, ops::Range, path::Path};
+
+use crate::{
+    open_in_editor, read_source, CodeIndex, IndexQuery, RankedIndex, SourceEncoding, SymbolKind,
+};
+
+/**
+* 全屏交互模式的状态
+*/
+struct TuiState<'a> {
+    index: &'a [CodeIndex],
+    root: &'a Path,
+    kinds: &'a [SymbolKind],
+    /// 预览时读取源码使用的编码
+    encoding: SourceEncoding,
+    input: String,
+    /// 光标在输入中的字符位置
+    cursor: usize,
+    results: Vec<RankedIndex<'a>>,
+    /// 查询语法错误或编辑器启动失败的信息
+    error: Option<String>,
+    list_state: ListState,
+    /// 最近预览的文件，切换结果时避免重复读取
+    preview: Option<(String, Vec<String>)>,
+}
+
+/**
+* 全屏交互模式：输入时实时更新结果，方向键选择，右侧预览代码，回车在编辑器中打开选中的结果
+*/
+pub fn run_tui(
+    index: &[CodeIndex],
+    root: &Path,
+    kinds: &[SymbolKind],
+    encoding: SourceEncoding,
+) -> io::Result<()> {
+    let mut state = TuiState::new(index, root, kinds, encoding);
+    let mut terminal = ratatui::init();
+    let result = state.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl<'a> TuiState<'a> {
+    fn new(
+        index: &'a [CodeIndex],
+        root: &'a Path,
+        kinds: &'a [SymbolKind],
+        encoding: SourceEncoding,
+    ) -> TuiState<'a> {
+        TuiState {
+            index,
+            root,
+            kinds,
+            encoding,
+            input: String::new(),
+            cursor: 0,
+            results: vec![],
+            error: None,
+            list_state: ListState::default(),
+            preview: None,
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match self.handle_key(key) {
+                KeyAction::Continue => {}
+                KeyAction::Quit => return Ok(()),
+                KeyAction::Select => {
+                    if let Some(selected) = self.selected() {
+                        // 编辑器运行期间恢复终端，退出后在原来的 terminal 上重新进入全屏界面，
+                        // 不再调用 ratatui::init，避免重复安装 panic hook
+                        ratatui::restore();
+                        let status = open_in_editor(&selected.path, selected.line);
+                        enable_raw_mode()?;
+                        execute!(io::stdout(), EnterAlternateScreen)?;
+                        terminal.clear()?;
+                        if let Err(err) = status {
+                            self.error = Some(err.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> KeyAction {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return KeyAction::Quit,
+            KeyCode::Char('c') | KeyCode::Char('d') if ctrl => return KeyAction::Quit,
+            KeyCode::Enter => return KeyAction::Select,
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Char('p') if ctrl => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::Char('n') if ctrl => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-10),
+            KeyCode::PageDown => self.move_selection(10),
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.input.chars().count()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.input.chars().count(),
+            KeyCode::Char('u') if ctrl => {
+                self.input.clear();
+                self.cursor = 0;
+                self.update_results();
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.input.remove(self.byte_index(self.cursor));
+                self.update_results();
+            }
+            KeyCode::Delete if self.cursor < self.input.chars().count() => {
+                self.input.remove(self.byte_index(self.cursor));
+                self.update_results();
+            }
+            KeyCode::Char(ch) if !ctrl => {
+                self.input.insert(self.byte_index(self.cursor), ch);
+                self.cursor += 1;
+                self.update_results();
+            }
+            _ => {}
+        }
+        KeyAction::Continue
+    }
+
+    fn byte_index(&self, cursor: usize) -> usize {
+        self.input
+            .char_indices()
+            .nth(cursor)
+            .map_or(self.input.len(), |(index, _)| index)
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        if self.results.is_empty() {
+            return;
+        }
+        let selected = self.list_state.selected().unwrap_or(0) as isize + offset;
+        let selected = selected.clamp(0, self.results.len() as isize - 1);
+        self.list_state.select(Some(selected as usize));
+    }
+
+    fn selected(&self) -> Option<&'a CodeIndex> {
+        self.list_state
+            .selected()
+            .and_then(|selected| self.results.get(selected))
+            .map(|ranked| ranked.item)
+    }
+
+    /**
+     * 输入变化后重新查询，查询语法错误时保留错误信息并清空结果
+     */
+    fn update_results(&mut self) {
+        self.results.clear();
+        self.error = None;
+        if !self.input.trim().is_empty() {
+            match IndexQuery::parse(&self.input) {
+                Ok(query) => {
+                    let items = self
+                        .index
+                        .iter()
+                        .filter(|item| self.kinds.is_empty() || self.kinds.contains(&item.kind))
+                        .filter(|item| query.matches(item))
+                        .collect();
+                    self.results = query.rank(items, self.root);
+                }
+                Err(err) => self.error = Some(err),
+            }
+        }
+        let selected = (!self.results.is_empty()).then_some(0);
+        self.list_state.select(selected);
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [input_area, main_area, status_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [list_area, preview_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(main_area);
+
+        let prompt = Span::styled(">> ", Style::default().fg(Color::Green));
+        let before_cursor: String = self.input.chars().take(self.cursor).collect();
+        let cursor_x =
+            input_area.x + 1 + prompt.width() as u16 + Span::raw(before_cursor).width() as u16;
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![prompt, Span::raw(self.input.as_str())]))
+                .block(Block::bordered().title(format!("当前根路径为 : {}", self.root.display()))),
+            input_area,
+        );
+        frame.set_cursor_position(Position::new(
+            cursor_x.min(input_area.right().saturating_sub(2)),
+            input_area.y + 1,
+        ));
+
+        let items: Vec<ListItem> = self
+            .results
+            .iter()
+            .map(|ranked| ListItem::new(self.result_line(ranked)))
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(format!("结果 {}", self.results.len())))
+            .highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            );
+        frame.render_stateful_widget(list, list_area, &mut self.list_state);
+
+        self.draw_preview(frame, preview_area);
+
+        let status = match &self.error {
+            Some(err) => Line::styled(err.as_str(), Style::default().fg(Color::Red)),
+            None => Line::styled(
+                "↑↓ 选择  Enter 在编辑器中打开  Ctrl-U 清空  Esc 退出",
+                Style::default().fg(Color::DarkGray),
+            ),
+        };
+        frame.render_widget(Paragraph::new(status), status_area);
+    }
+
+    fn result_line(&self, ranked: &RankedIndex) -> Line<'static> {
+        let item = ranked.item;
+        let mut spans = vec![Span::styled(
+            format!("{:<9}", item.kind.as_str()),
+            Style::default().fg(Color::Magenta),
+        )];
+        spans.extend(highlight_spans(&item.qualified_name, &ranked.highlights));
+        spans.push(Span::styled(
+            format!("  {}:{}", self.relative_path(item), item.line),
+            Style::default().fg(Color::DarkGray),
+        ));
+        Line::from(spans)
+    }
+
+    fn relative_path(&self, item: &CodeIndex) -> String {
+        let path = Path::new(&item.path);
+        path.strip_prefix(self.root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+
+    /**
+     * 预览选中结果附近的代码，选中的行位于上方三分之一处
+     */
+    fn draw_preview(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(item) = self.selected() else {
+            frame.render_widget(Block::bordered().title("预览"), area);
+            return;
+        };
+        if self
+            .preview
+            .as_ref()
+            .is_none_or(|(path, _)| *path != item.path)
+        {
+            let lines = read_source(&item.path, self.encoding)
+                .map(|code| {
+                    code.lines()
+                        .map(|line| line.replace('\t', "    "))
+                        .collect()
+                })
+                .unwrap_or_default();
+            self.preview = Some((item.path.clone(), lines));
+        }
+        let lines = self.preview.as_ref().map_or(&[][..], |(_, lines)| lines);
+        let height = area.height.saturating_sub(2) as usize;
+        let start = item.line.saturating_sub(height / 3 + 1);
+        let number_width = (start + height).to_string().len();
+        let preview: Vec<Line> = lines
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(height)
+            .map(|(index, code)| {
+                let number = Span::styled(
+                    format!("{:>number_width$} ", index + 1),
+                    Style::default().fg(Color::DarkGray),
+                );
+                if index + 1 == item.line {
+                    Line::from(vec![
+                        number,
+                        Span::styled(
+                            code.as_str(),
+                            Style::default()
+                                .fg(Color::Yellow)
+                                .add_modifier(Modifier::BOLD),
+                        ),
+                    ])
+                } else {
+                    Line::from(vec![number, Span::raw(code.as_str())])
+                }
+            })
+            .collect();
+        let title = format!("{}:{}", self.relative_path(item), item.line);
+        frame.render_widget(
+            Paragraph::new(preview).block(Block::bordered().title(title)),
+            area,
+        );
+    }
+}
+
+enum KeyAction {
+    Continue,
+    Quit,
+    Select,
+}
+
+/**
+* 按字节范围拆分出需要高亮的片段
+*/
+fn highlight_spans(text: &str, ranges: &[Range<usize>]) -> Vec<Span<'static>> {
+    let mut ranges = ranges.to_vec();
+    ranges.sort_by_key(|range| range.start);
+    let mut spans = vec![];
+    let mut last = 0;
+    for range in ranges {
+        if range.end <= last {
+            continue;
+        }
+        let start = range.start.max(last);
+        spans.push(Span::raw(text[last..start].to_string()));
+        spans.push(Span::styled(
+            text[start..range.end].to_string(),
+            Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::BOLD),
+        ));
+        last = range.end;
+    }
+    spans.push(Span::raw(text[last..].to_string()));
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::code_index;
+
+    fn press(state: &mut TuiState, code: KeyCode) -> KeyAction {
+        state.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn ctrl(state: &mut TuiState, ch: char) -> KeyAction {
+        state.handle_key(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::CONTROL))
+    }
+
+    #[test]
+    fn test_edit_multibyte_input() {
+        let mut state = TuiState::new(&[], Path::new("/p"), &[], SourceEncoding::default());
+        for ch in "面积x".chars() {
+            press(&mut state, KeyCode::Char(ch));
+        }
+        assert_eq!((state.input.as_str(), state.cursor), ("面积x", 3));
+        assert_eq!(state.byte_index(1), 3);
+        assert_eq!(state.byte_index(3), state.input.len());
+
+        press(&mut state, KeyCode::Left);
+        press(&mut state, KeyCode::Backspace);
+        assert_eq!((state.input.as_str(), state.cursor), ("面x", 1));
+        press(&mut state, KeyCode::Char('算'));
+        assert_eq!((state.input.as_str(), state.cursor), ("面算x", 2));
+        press(&mut state, KeyCode::Home);
+        press(&mut state, KeyCode::Delete);
+        assert_eq!((state.input.as_str(), state.cursor), ("算x", 0));
+        // 光标不会越过输入的两端
+        press(&mut state, KeyCode::Left);
+        assert_eq!(state.cursor, 0);
+        press(&mut state, KeyCode::End);
+        press(&mut state, KeyCode::Right);
+        assert_eq!(state.cursor, 2);
+        press(&mut state, KeyCode::Delete);
+        assert_eq!(state.input, "算x");
+
+        ctrl(&mut state, 'u');
+        assert_eq!((state.input.as_str(), state.cursor), ("", 0));
+        assert!(matches!(
+            press(&mut state, KeyCode::Enter),
+            KeyAction::Select
+        ));
+        assert!(matches!(press(&mut state, KeyCode::Esc), KeyAction::Quit));
+        assert!(matches!(ctrl(&mut state, 'c'), KeyAction::Quit));
+    }
+
+    #[test]
+    fn test_update_results() {
+        let index = [
+            code_index("/p/src/lib.rs", "parse_code", SymbolKind::Function),
+            code_index("/p/src/lib.rs", "Parser", SymbolKind::Struct),
+            code_index("/p/src/main.rs", "main", SymbolKind::Function),
+        ];
+        let kinds = [SymbolKind::Function];
+        let mut state = TuiState::new(&index, Path::new("/p"), &kinds, SourceEncoding::default());
+        for ch in "pars".chars() {
+            press(&mut state, KeyCode::Char(ch));
+        }
+        // 只保留指定类型的符号，并选中第一个结果
+        let names: Vec<&str> = state
+            .results
+            .iter()
+            .map(|ranked| ranked.item.qualified_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["parse_code"]);
+        assert_eq!(state.list_state.selected(), Some(0));
+        assert_eq!(
+            state.selected().map(|item| item.line_code.as_str()),
+            Some("parse_code")
+        );
+
+        // 查询语法错误时清空结果和选中项
+        state.input = String::from("(kind:fn");
+        state.update_results();
+        assert!(state.results.is_empty());
+        assert!(state.error.is_some());
+        assert_eq!(state.list_state.selected(), None);
+        assert!(state.selected().is_none());
+
+        // 修正后清除错误信息
+        ctrl(&mut state, 'u');
+        assert!(state.error.is_none());
+        assert!(state.results.is_empty());
+    }
+
+    #[test]
+    fn test_move_selection() {
+        let index: Vec<CodeIndex> = (0..15)
+            .map(|i| code_index("/p/a.rs", &format!("item{i}"), SymbolKind::Function))
+            .collect();
+        let mut state = TuiState::new(&index, Path::new("/p"), &[], SourceEncoding::default());
+        // 没有结果时不选中任何项
+        state.move_selection(1);
+        assert_eq!(state.list_state.selected(), None);
+
+        for ch in "item".chars() {
+            press(&mut state, KeyCode::Char(ch));
+        }
+        assert_eq!(state.results.len(), 15);
+        press(&mut state, KeyCode::Up);
+        assert_eq!(state.list_state.selected(), Some(0));
+        press(&mut state, KeyCode::PageDown);
+        assert_eq!(state.list_state.selected(), Some(10));
+        press(&mut state, KeyCode::PageDown);
+        assert_eq!(state.list_state.selected(), Some(14));
+        ctrl(&mut state, 'p');
+        assert_eq!(state.list_state.selected(), Some(13));
+        press(&mut state, KeyCode::PageUp);
+        press(&mut state, KeyCode::PageUp);
+        assert_eq!(state.list_state.selected(), Some(0));
+    }
+}